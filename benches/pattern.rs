@@ -0,0 +1,54 @@
+fn random_data(len: usize) -> Vec<u8> {
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+fn bench<F: FnMut() -> Vec<usize>>(name: &str, iters: u32, mut f: F) -> Vec<usize> {
+    let mut result = f();
+
+    let start = ::std::time::Instant::now();
+
+    for _ in 0..iters {
+        result = ::std::hint::black_box(f());
+    }
+
+    let elapsed = start.elapsed() / iters;
+
+    println!("{:<32} {:>12.3?}", name, elapsed);
+
+    result
+}
+
+fn main() {
+    let mut data = random_data(64 * 1024 * 1024);
+
+    let sig = [0x48, 0x8B, 0x05, 0x11, 0x22, 0x33, 0x44, 0x48, 0x85, 0xC0];
+
+    for offset in [0x1000, 0x20_0000, 0x3FF_0000] {
+        data[offset..offset + sig.len()].copy_from_slice(&sig);
+    }
+
+    for pat in [
+        "48 8B 05 ?? ?? ?? ?? 48 85 C0",
+        "48 8B 05 ?? ?? ?? ?? 4? 85 C?",
+        "E8 ?? ?? ?? ?? 90",
+        "?? ?? 05 11",
+    ] {
+        let pat = vcheat::pattern::Pattern::parse(pat).unwrap();
+
+        println!("{}", pat);
+
+        let scalar = bench("  scan_scalar", 5, || pat.scan_scalar(&data));
+        let simd = bench("  scan", 5, || pat.scan(&data));
+
+        assert_eq!(scalar, simd);
+    }
+}
@@ -2,6 +2,7 @@ use crate::{AnyResult, HANDLE};
 
 #[doc = "Return value: `Handle`"]
 #[inline]
+#[cfg(windows)]
 pub unsafe fn open_proc(pid: u32) -> AnyResult<HANDLE> {
     let proc_handle = crate::ffi::OpenProcess(0x1F0FFF, 0, pid);
 
@@ -13,6 +14,7 @@ pub unsafe fn open_proc(pid: u32) -> AnyResult<HANDLE> {
 }
 
 #[inline]
+#[cfg(windows)]
 pub unsafe fn close_handle(handle: HANDLE) -> AnyResult<()> {
     if 0 == crate::ffi::CloseHandle(handle) {
         return Err(::std::io::Error::last_os_error().into());
@@ -21,7 +23,33 @@ pub unsafe fn close_handle(handle: HANDLE) -> AnyResult<()> {
     Ok(())
 }
 
+#[doc = r"Return value: `Handle`
+
+On Linux the handle is the pid itself"]
+#[cfg(target_os = "linux")]
+pub unsafe fn open_proc(pid: u32) -> AnyResult<HANDLE> {
+    if !::std::path::Path::new(&format!("/proc/{}", pid)).exists() {
+        return Err(format!("{} not found", pid).into());
+    }
+
+    Ok(pid as HANDLE)
+}
+
+#[cfg(target_os = "linux")]
+pub unsafe fn close_handle(_handle: HANDLE) -> AnyResult<()> {
+    Ok(())
+}
+
+#[doc = r"Return value: `PointerWidth` of the target
+
+Detected on every call, `memory::RemoteMemory` detects it once and keeps it.
+A WOW64 process is 32-bit on Windows; on Linux the PE headers of the `.exe` decide for Wine processes, else the ELF class of `/proc/<pid>/exe`"]
+pub unsafe fn get_pointer_width(proc_handle: HANDLE) -> AnyResult<crate::memory::PointerWidth> {
+    detect_pointer_width(proc_handle)
+}
+
 #[doc = "Return value: `Process id`"]
+#[cfg(windows)]
 pub unsafe fn get_pid<S: AsRef<str>>(proc_name: S) -> AnyResult<u32> {
     let snapshot_handle: HANDLE = crate::ffi::CreateToolhelp32Snapshot(0x2, 0x0);
 
@@ -66,6 +94,7 @@ pub unsafe fn get_pid<S: AsRef<str>>(proc_name: S) -> AnyResult<u32> {
 }
 
 #[doc = "Return value: `Vec<types::ProcInfo>`"]
+#[cfg(windows)]
 pub unsafe fn get_all_proc_info() -> AnyResult<Vec<crate::types::ProcInfo>> {
     let snapshot_handle: HANDLE = crate::ffi::CreateToolhelp32Snapshot(0x2, 0x0);
 
@@ -111,7 +140,29 @@ pub unsafe fn get_all_proc_info() -> AnyResult<Vec<crate::types::ProcInfo>> {
     Ok(procs_info)
 }
 
+#[doc = r"Return value: `Process id`
+
+Matches `name` or `comm` of `get_all_proc_info`, so Wine/Proton processes are found by their Windows exe name"]
+#[cfg(target_os = "linux")]
+pub unsafe fn get_pid<S: AsRef<str>>(proc_name: S) -> AnyResult<u32> {
+    crate::linux::all_proc_info()?
+        .into_iter()
+        .find(|proc_info| {
+            proc_info.name.eq_ignore_ascii_case(proc_name.as_ref())
+                || proc_info.comm.eq_ignore_ascii_case(proc_name.as_ref())
+        })
+        .map(|proc_info| proc_info.id)
+        .ok_or_else(|| format!("{} not found", proc_name.as_ref()).into())
+}
+
+#[doc = "Return value: `Vec<types::ProcInfo>`"]
+#[cfg(target_os = "linux")]
+pub unsafe fn get_all_proc_info() -> AnyResult<Vec<crate::types::ProcInfo>> {
+    crate::linux::all_proc_info()
+}
+
 #[doc = "Return value: `ModInfo`"]
+#[cfg(windows)]
 pub unsafe fn get_mod_info<S: AsRef<str>>(
     pid: u32,
     mod_name: S,
@@ -171,6 +222,7 @@ pub unsafe fn get_mod_info<S: AsRef<str>>(
 }
 
 #[doc = "Return value: `Vec<ModInfo>`"]
+#[cfg(windows)]
 pub unsafe fn get_all_mod_info(pid: u32) -> AnyResult<Vec<crate::types::ModInfo>> {
     let snapshot_handle: HANDLE = crate::ffi::CreateToolhelp32Snapshot(0x8 | 0x10, pid);
 
@@ -220,15 +272,79 @@ pub unsafe fn get_all_mod_info(pid: u32) -> AnyResult<Vec<crate::types::ModInfo>
     Ok(mods_info)
 }
 
+#[doc = r"Return value: `ModInfo`
+
+Built from `/proc/<pid>/maps`, `handle` is the base address"]
+#[cfg(target_os = "linux")]
+pub unsafe fn get_mod_info<S: AsRef<str>>(
+    pid: u32,
+    mod_name: S,
+) -> AnyResult<crate::types::ModInfo> {
+    crate::linux::all_mod_info(pid)?
+        .into_iter()
+        .find(|mod_info| mod_info.name.eq_ignore_ascii_case(mod_name.as_ref()))
+        .ok_or_else(|| format!("{} not found", mod_name.as_ref()).into())
+}
+
+#[doc = r"Return value: `Vec<ModInfo>`
+
+Consecutive mappings of the same file are one module; PE images mapped by Wine span their `SizeOfImage`"]
+#[cfg(target_os = "linux")]
+pub unsafe fn get_all_mod_info(pid: u32) -> AnyResult<Vec<crate::types::ModInfo>> {
+    crate::linux::all_mod_info(pid)
+}
+
+// `proc_handle` needs `PROCESS_QUERY_LIMITED_INFORMATION`
+#[cfg(windows)]
+unsafe fn detect_pointer_width(proc_handle: HANDLE) -> AnyResult<crate::memory::PointerWidth> {
+    use crate::memory::PointerWidth;
+
+    let mut is_wow64: crate::BOOL = 0;
+
+    if 0 == crate::ffi::IsWow64Process(proc_handle, &mut is_wow64) {
+        return Err(::std::io::Error::last_os_error().into());
+    }
+
+    if 0 != is_wow64 {
+        return Ok(PointerWidth::Bits32);
+    }
+
+    if PointerWidth::Bits64 == PointerWidth::native() {
+        return Ok(PointerWidth::Bits64);
+    }
+
+    // A 32-bit build sees native 64-bit processes as non-WOW64 as well
+    if 0 == crate::ffi::IsWow64Process(crate::ffi::GetCurrentProcess(), &mut is_wow64) {
+        return Err(::std::io::Error::last_os_error().into());
+    }
+
+    match is_wow64 {
+        0 => Ok(PointerWidth::Bits32),
+        _ => Ok(PointerWidth::Bits64),
+    }
+}
+
+#[cfg(target_os = "linux")]
+unsafe fn detect_pointer_width(proc_handle: HANDLE) -> AnyResult<crate::memory::PointerWidth> {
+    crate::linux::pointer_width(proc_handle as u32)
+}
+
 #[doc = "Return value: `Allocated memory address`"]
+#[cfg(windows)]
 pub unsafe fn alloc_mem(
     proc_handle: HANDLE,
-    addr: *const ::core::ffi::c_void,
+    addr: impl Into<crate::memory::RemotePtr<::core::ffi::c_void>>,
     size: usize,
     mem_alloc: u32,
     mem_protect: u32,
 ) -> AnyResult<*mut ::core::ffi::c_void> {
-    let addr = crate::ffi::VirtualAllocEx(proc_handle, addr, size, mem_alloc, mem_protect);
+    let addr = crate::ffi::VirtualAllocEx(
+        proc_handle,
+        addr.into().as_ptr(),
+        size,
+        mem_alloc,
+        mem_protect,
+    );
 
     if ::core::ptr::null_mut() == addr {
         return Err(::std::io::Error::last_os_error().into());
@@ -238,9 +354,10 @@ pub unsafe fn alloc_mem(
 }
 
 #[doc = "If the fourth parameter is **RELEASE/0x8000**, the third parameter must be 0"]
+#[cfg(windows)]
 pub unsafe fn free_mem(
     proc_handle: HANDLE,
-    addr: *mut ::core::ffi::c_void,
+    addr: impl Into<crate::memory::RemotePtr<::core::ffi::c_void>>,
     mut size: usize,
     mem_free: u32,
 ) -> AnyResult<()> {
@@ -248,7 +365,8 @@ pub unsafe fn free_mem(
         size = 0;
     }
 
-    if 0 == crate::ffi::VirtualFreeEx(proc_handle, addr, size, mem_free) {
+    if 0 == crate::ffi::VirtualFreeEx(proc_handle, addr.into().as_ptr().cast_mut(), size, mem_free)
+    {
         return Err(::std::io::Error::last_os_error().into());
     }
 
@@ -256,16 +374,17 @@ pub unsafe fn free_mem(
 }
 
 #[doc = "Return value: `(BaseAddress, RegionSize, AllocationProtect, Type, State, Protect)`"]
+#[cfg(windows)]
 pub unsafe fn query_mem(
     proc_handle: HANDLE,
-    addr: *const ::core::ffi::c_void,
+    addr: impl Into<crate::memory::RemotePtr<::core::ffi::c_void>>,
 ) -> AnyResult<crate::types::MemInfo> {
     let mut mbi: crate::ffi::MemoryBasicInformation =
         ::core::mem::zeroed::<crate::ffi::MemoryBasicInformation>();
 
     if 0 == crate::ffi::VirtualQueryEx(
         proc_handle,
-        addr,
+        addr.into().as_ptr(),
         &mut mbi,
         ::core::mem::size_of::<crate::ffi::MemoryBasicInformation>(),
     ) {
@@ -279,15 +398,22 @@ pub unsafe fn query_mem(
     })
 }
 
+#[cfg(windows)]
 pub unsafe fn protect_mem(
     proc_handle: HANDLE,
-    addr: *const ::core::ffi::c_void,
+    addr: impl Into<crate::memory::RemotePtr<::core::ffi::c_void>>,
     size: usize,
     mem_protect: u32,
 ) -> AnyResult<u32> {
     let mut prev_prot: u32 = Default::default();
 
-    if 0 == crate::ffi::VirtualProtectEx(proc_handle, addr, size, mem_protect, &mut prev_prot) {
+    if 0 == crate::ffi::VirtualProtectEx(
+        proc_handle,
+        addr.into().as_ptr(),
+        size,
+        mem_protect,
+        &mut prev_prot,
+    ) {
         return Err(::std::io::Error::last_os_error().into());
     }
 
@@ -295,6 +421,7 @@ pub unsafe fn protect_mem(
 }
 
 #[doc = "Remote DLL Injection"]
+#[cfg(windows)]
 pub unsafe fn inject_dll<S: AsRef<str>>(proc_handle: HANDLE, dll_path: S) -> AnyResult<()> {
     let dll_path_buf = format!("{}\0", dll_path.as_ref())
         .to_string()
@@ -367,6 +494,7 @@ pub unsafe fn inject_dll<S: AsRef<str>>(proc_handle: HANDLE, dll_path: S) -> Any
 }
 
 #[doc = "Remote DLL Ejection"]
+#[cfg(windows)]
 pub unsafe fn eject_dll(
     proc_handle: HANDLE,
     mod_handle: HANDLE,
@@ -423,59 +551,72 @@ pub unsafe fn eject_dll(
     Ok(())
 }
 
-#[doc = "Return value: `Final pointer`"]
-pub unsafe fn read_multi_pointer(
-    proc_handle: HANDLE,
-    mut base_addr: *const ::core::ffi::c_void,
-    byte_offsets: &[isize],
-) -> AnyResult<*const ::core::ffi::c_void> {
-    {
-        let mut mbi = query_mem(proc_handle, base_addr)?;
-
-        if mbi.state != crate::types::mem_alloc::COMMIT {
-            return Err("The mem is not commit".into());
-        }
-
-        protect_mem(
-            proc_handle,
-            base_addr,
-            0x1000,
-            mbi.protect | crate::types::mem_protect::READ_WRITE,
-        )?;
+#[doc = r"Return value: `T`
 
-        base_addr = base_addr.read() as isize as *const ::core::ffi::c_void;
+Fails unless all `size_of::<T>()` bytes are read"]
+pub unsafe fn read<T: crate::memory::Pod>(
+    proc_handle: HANDLE,
+    addr: impl Into<crate::memory::RemotePtr<T>>,
+) -> AnyResult<T> {
+    addr.into().read(&byte_memory(proc_handle))
+}
 
-        protect_mem(proc_handle, base_addr, 0x1000, mbi.protect)?;
+#[doc = "Return value: `Vec<T>` of `n` consecutive values"]
+pub unsafe fn read_slice<T: crate::memory::Pod>(
+    proc_handle: HANDLE,
+    addr: impl Into<crate::memory::RemotePtr<T>>,
+    n: usize,
+) -> AnyResult<Vec<T>> {
+    addr.into().read_slice(&byte_memory(proc_handle), n)
+}
 
-        for byte_offset in byte_offsets {
-            base_addr = base_addr.byte_offset(*byte_offset);
+pub unsafe fn write<T: crate::memory::Pod>(
+    proc_handle: HANDLE,
+    addr: impl Into<crate::memory::RemotePtr<T>>,
+    value: &T,
+) -> AnyResult<()> {
+    addr.into().write(&byte_memory(proc_handle), value)
+}
 
-            mbi = query_mem(proc_handle, base_addr)?;
+#[doc = r"Return value: `Vec<Result>`, one per request
 
-            if mbi.state != crate::types::mem_alloc::COMMIT {
-                return Err("The mem is not commit".into());
-            }
+On Linux a single `process_vm_readv` serves up to 1024 requests"]
+pub unsafe fn read_batch(
+    proc_handle: HANDLE,
+    requests: &mut [crate::memory::ReadRequest<'_>],
+) -> Vec<AnyResult<()>> {
+    crate::memory::MemoryAccess::read_batch(&byte_memory(proc_handle), requests)
+}
 
-            protect_mem(
-                proc_handle,
-                base_addr,
-                0x1000,
-                mbi.protect | crate::types::mem_protect::READ_WRITE,
-            )?;
+// Plain byte access never looks at the pointer width, so there is nothing to detect
+fn byte_memory(proc_handle: HANDLE) -> crate::memory::RemoteMemory {
+    crate::memory::RemoteMemory::from_handle(proc_handle, crate::memory::PointerWidth::native())
+}
 
-            base_addr = base_addr.read() as isize as *const ::core::ffi::c_void;
+#[doc = r"Return value: `Final pointer`
 
-            protect_mem(proc_handle, base_addr, 0x1000, mbi.protect)?;
-        }
+Dereferences `base_addr`, then for every offset adds it and dereferences again, with pointers as wide as `get_pointer_width` says.
+The width is detected on every call, keep a `memory::RemoteMemory` for repeated chains.
+Page protections are left alone; the error is a `memory::PointerChainError` naming the failed hop"]
+pub unsafe fn read_multi_pointer(
+    proc_handle: HANDLE,
+    base_addr: impl Into<crate::memory::RemotePtr<::core::ffi::c_void>>,
+    byte_offsets: &[isize],
+) -> AnyResult<*const ::core::ffi::c_void> {
+    let final_ptr = crate::memory::MemoryAccess::read_multi_pointer(
+        &crate::memory::RemoteMemory::new(proc_handle)?,
+        base_addr.into().addr(),
+        byte_offsets,
+    )?;
 
-        Ok(base_addr)
-    }
+    Ok(final_ptr as *const ::core::ffi::c_void)
 }
 
 #[doc = "Return value: `Exec/Read/Write?`"]
+#[cfg(windows)]
 pub unsafe fn check_mem_protect(
     proc_handle: HANDLE,
-    addr: *const ::core::ffi::c_void,
+    addr: impl Into<crate::memory::RemotePtr<::core::ffi::c_void>>,
     mem_query_protect: crate::types::MemQueryProtect,
 ) -> AnyResult<bool> {
     let mbi = query_mem(proc_handle, addr)?;
@@ -517,3 +658,160 @@ pub unsafe fn check_mem_protect(
 
     Ok(is_protect_able && is_commit)
 }
+
+#[doc = r"Iterator over the regions of a process, in address order
+
+Free memory is skipped; every filter that is set must hold"]
+#[derive(Debug)]
+pub struct Regions {
+    #[cfg(windows)]
+    proc_handle: HANDLE,
+    #[cfg(windows)]
+    next_addr: Option<usize>,
+    #[cfg(target_os = "linux")]
+    regions: ::std::vec::IntoIter<crate::types::MemRegion>,
+    readable: bool,
+    writable: bool,
+    executable: bool,
+    private: bool,
+    image: bool,
+}
+
+impl Regions {
+    pub fn readable(mut self) -> Self {
+        self.readable = true;
+
+        self
+    }
+
+    pub fn writable(mut self) -> Self {
+        self.writable = true;
+
+        self
+    }
+
+    pub fn executable(mut self) -> Self {
+        self.executable = true;
+
+        self
+    }
+
+    pub fn private(mut self) -> Self {
+        self.private = true;
+
+        self
+    }
+
+    pub fn image(mut self) -> Self {
+        self.image = true;
+
+        self
+    }
+
+    fn accepts(&self, region: &crate::types::MemRegion) -> bool {
+        (!self.readable || region.is_readable())
+            && (!self.writable || region.is_writable())
+            && (!self.executable || region.is_executable())
+            && (!self.private || region.is_private())
+            && (!self.image || region.is_image())
+    }
+
+    #[cfg(windows)]
+    fn next_region(&mut self) -> Option<crate::types::MemRegion> {
+        loop {
+            let addr = self.next_addr?;
+
+            let mut mbi: crate::ffi::MemoryBasicInformation =
+                unsafe { ::core::mem::zeroed::<crate::ffi::MemoryBasicInformation>() };
+
+            if 0 == unsafe {
+                crate::ffi::VirtualQueryEx(
+                    self.proc_handle,
+                    addr as *const ::core::ffi::c_void,
+                    &mut mbi,
+                    ::core::mem::size_of::<crate::ffi::MemoryBasicInformation>(),
+                )
+            } {
+                self.next_addr = None;
+
+                return None;
+            }
+
+            let base = mbi.base_address as usize;
+
+            self.next_addr = base
+                .checked_add(mbi.region_size)
+                .filter(|next_addr| *next_addr > addr);
+
+            // MEM_FREE
+            if mbi.state == 0x10000 {
+                continue;
+            }
+
+            let mut path_buf = [0u16; 1024];
+
+            let len = match mbi.type_ {
+                crate::types::mem_type::PRIVATE => 0,
+                _ => unsafe {
+                    crate::ffi::GetMappedFileNameW(
+                        self.proc_handle,
+                        mbi.base_address,
+                        path_buf.as_mut_ptr(),
+                        path_buf.len() as u32,
+                    )
+                },
+            };
+
+            return Some(crate::types::MemRegion {
+                base,
+                size: mbi.region_size,
+                alloc_base: mbi.allocation_base as usize,
+                alloc_protect: mbi.allocation_protect,
+                protect: mbi.protect,
+                state: mbi.state,
+                type_: mbi.type_,
+                path: String::from_utf16_lossy(&path_buf[..len as usize]),
+            });
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn next_region(&mut self) -> Option<crate::types::MemRegion> {
+        self.regions.next()
+    }
+}
+
+impl Iterator for Regions {
+    type Item = crate::types::MemRegion;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let region = self.next_region()?;
+
+            if self.accepts(&region) {
+                return Some(region);
+            }
+        }
+    }
+}
+
+#[doc = r"Return value: `Regions`
+
+`vcheat::external::regions(proc_handle)?.readable().private()` = every committed, readable, private region
+
+Windows queries lazily with `VirtualQueryEx`, Linux reads `/proc/<pid>/smaps` up front"]
+pub unsafe fn regions(proc_handle: HANDLE) -> AnyResult<Regions> {
+    Ok(Regions {
+        #[cfg(windows)]
+        proc_handle,
+        #[cfg(windows)]
+        next_addr: Some(0),
+        #[cfg(target_os = "linux")]
+        regions: crate::linux::regions(proc_handle as u32)?.into_iter(),
+        readable: false,
+        writable: false,
+        executable: false,
+        private: false,
+        image: false,
+    })
+}
@@ -1,5 +1,7 @@
+#[cfg(windows)]
 use crate::{BOOL, HANDLE};
 
+#[cfg(windows)]
 #[link(name = "Psapi")]
 extern "system" {
     pub(crate) fn GetModuleInformation(
@@ -8,8 +10,16 @@ extern "system" {
         lpmodinfo: *mut ModuleInfo,
         cb: u32,
     ) -> BOOL;
+
+    pub(crate) fn GetMappedFileNameW(
+        hProcess: HANDLE,
+        lpv: *const ::core::ffi::c_void,
+        lpFilename: *mut u16,
+        nSize: u32,
+    ) -> u32;
 }
 
+#[cfg(windows)]
 #[link(name = "Kernel32")]
 extern "system" {
 
@@ -19,6 +29,8 @@ extern "system" {
 
     pub(crate) fn GetCurrentProcess() -> HANDLE;
 
+    pub(crate) fn IsWow64Process(hProcess: HANDLE, Wow64Process: *mut BOOL) -> BOOL;
+
     pub(crate) fn GetModuleHandleW(lpModuleName: *const u16) -> HANDLE;
 
     pub(crate) fn WaitForSingleObject(hHandle: HANDLE, dwMilliseconds: u32) -> u32;
@@ -143,6 +155,7 @@ extern "system" {
     pub(crate) fn FreeLibraryAndExitThread(hLibModule: HANDLE, dwExitCode: u32) -> !;
 }
 
+#[cfg(windows)]
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) struct SecurityAttributes {
@@ -151,6 +164,7 @@ pub(crate) struct SecurityAttributes {
     pub(crate) b_inherit_handle: i32,
 }
 
+#[cfg(windows)]
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) struct ProcessEntry32W {
@@ -166,6 +180,7 @@ pub(crate) struct ProcessEntry32W {
     pub(crate) sz_exe_file: [u16; 260],
 }
 
+#[cfg(windows)]
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) struct ModuleEntry32W {
@@ -181,6 +196,7 @@ pub(crate) struct ModuleEntry32W {
     pub(crate) sz_exe_path: [u16; 260],
 }
 
+#[cfg(windows)]
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) struct MemoryBasicInformation {
@@ -195,6 +211,7 @@ pub(crate) struct MemoryBasicInformation {
     pub(crate) type_: u32,
 }
 
+#[cfg(windows)]
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) struct ModuleInfo {
@@ -202,3 +219,33 @@ pub(crate) struct ModuleInfo {
     pub(crate) size_of_image: u32,
     pub(crate) entry_point: *mut core::ffi::c_void,
 }
+
+// Linux, from libc
+#[cfg(target_os = "linux")]
+extern "C" {
+    pub(crate) fn process_vm_readv(
+        pid: i32,
+        local_iov: *const IoVec,
+        liovcnt: ::core::ffi::c_ulong,
+        remote_iov: *const IoVec,
+        riovcnt: ::core::ffi::c_ulong,
+        flags: ::core::ffi::c_ulong,
+    ) -> isize;
+
+    pub(crate) fn process_vm_writev(
+        pid: i32,
+        local_iov: *const IoVec,
+        liovcnt: ::core::ffi::c_ulong,
+        remote_iov: *const IoVec,
+        riovcnt: ::core::ffi::c_ulong,
+        flags: ::core::ffi::c_ulong,
+    ) -> isize;
+}
+
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct IoVec {
+    pub(crate) iov_base: *mut ::core::ffi::c_void,
+    pub(crate) iov_len: usize,
+}
@@ -163,6 +163,28 @@ pub unsafe fn free_dll_exit_thread(mod_handle: HANDLE, exit_code: u32) {
     crate::ffi::FreeLibraryAndExitThread(mod_handle, exit_code);
 }
 
+#[doc = r"Return value: `T`
+
+Fails unless all `size_of::<T>()` bytes are read"]
+pub unsafe fn read<T: crate::memory::Pod>(addr: *const ::core::ffi::c_void) -> AnyResult<T> {
+    crate::memory::MemoryAccess::read(&crate::memory::LocalMemory::new(), addr as usize)
+}
+
+#[doc = "Return value: `Vec<T>` of `n` consecutive values"]
+pub unsafe fn read_slice<T: crate::memory::Pod>(
+    addr: *const ::core::ffi::c_void,
+    n: usize,
+) -> AnyResult<Vec<T>> {
+    crate::memory::MemoryAccess::read_slice(&crate::memory::LocalMemory::new(), addr as usize, n)
+}
+
+pub unsafe fn write<T: crate::memory::Pod>(
+    addr: *const ::core::ffi::c_void,
+    value: &T,
+) -> AnyResult<()> {
+    crate::memory::MemoryAccess::write(&crate::memory::LocalMemory::new(), addr as usize, value)
+}
+
 #[doc = "Return value: `Final pointer`"]
 pub unsafe fn read_multi_pointer(
     mut base_addr: *const ::core::ffi::c_void,
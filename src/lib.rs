@@ -1,4 +1,5 @@
 // #![deny(missing_docs)]
+#![allow(clippy::missing_safety_doc)]
 #![doc = r#"
 [![Crates.io Version](https://img.shields.io/crates/v/vcheat?style=for-the-badge)](https://crates.io/crates/vcheat)
 [![Static Badge](https://img.shields.io/badge/Github-vcheat-green?style=for-the-badge)](https://github.com/vSylva/vcheat/)
@@ -6,115 +7,78 @@
 
 mod ffi;
 
+#[cfg(target_os = "linux")]
+mod linux;
+
 #[doc = "Location of memory constants"]
 pub mod types;
 
 #[doc = "Commonly used by `.exe`"]
+#[cfg(any(windows, target_os = "linux"))]
 pub mod external;
 
 #[doc = "Commonly used by `.dll`"]
+#[cfg(windows)]
 pub mod internal;
 
-pub type AnyResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
-
-pub type HANDLE = isize;
-pub type BOOL = i32;
-
-#[doc = r"Return value: `Offset`"]
-pub unsafe fn pat_find<S: AsRef<str>>(pat: S, data: &[u8]) -> AnyResult<usize> {
-    let mut pat_bytes: Vec<u8> = Vec::<u8>::new();
-
-    for pair in pat.as_ref().split_whitespace() {
-        if pair == "?" || pair == "??" || pair == "*" || pair == "**" {
-            pat_bytes.push(0);
-        } else {
-            let num: u8 = u8::from_str_radix(pair, 16)?;
+#[doc = "Memory access backends"]
+pub mod memory;
 
-            pat_bytes.push(num);
-        }
-    }
+#[doc = "Signature parsing and scanning"]
+pub mod pattern;
 
-    let data_len = data.len();
-    let pat_bytes_len = pat_bytes.len();
+#[doc = "Module-relative pointer paths"]
+pub mod pointer;
 
-    let mut skip_table = [pat_bytes.len(); 256];
+#[doc = "Signature post-processing"]
+pub mod resolver;
 
-    for (i, byte) in pat_bytes.iter().enumerate().take(pat_bytes.len() - 1).rev() {
-        if skip_table[*byte as usize] == pat_bytes.len() {
-            skip_table[*byte as usize] = pat_bytes.len() - 1 - i;
-        }
-    }
+#[doc = "Signature database"]
+pub mod sigdb;
 
-    let mut i = pat_bytes_len - 1;
+#[doc = "String search and code references"]
+pub mod strings;
 
-    while i < data_len {
-        let mut j = pat_bytes_len - 1;
-        let mut k = i;
+#[doc = "x86-64 instruction length and operand layout decoder"]
+pub mod x86;
 
-        while j > 0 && (data[k] == pat_bytes[j] || pat_bytes[j] == 0) {
-            k -= 1;
-            j -= 1;
-        }
+pub type AnyResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
-        if j == 0 && (data[k] == pat_bytes[j] || pat_bytes[j] == 0) {
-            return Ok(k);
-        }
+#[doc = "Process handle on Windows, the pid on Linux"]
+pub type HANDLE = isize;
+pub type BOOL = i32;
 
-        i += skip_table[data[i] as usize];
-    }
+#[doc = r"Return value: `Offset`
 
-    Err("\"pat\" not found".into())
+Accepts the extended syntax of `pattern::ExtPattern`"]
+pub unsafe fn pat_find<S: AsRef<str>>(pat: S, data: &[u8]) -> AnyResult<usize> {
+    crate::pattern::ExtPattern::parse(pat)?
+        .find(data)
+        .ok_or_else(|| "\"pat\" not found".into())
 }
 
-#[doc = r"Return value: `Vec<Offset>`"]
-pub unsafe fn pat_scan<S: AsRef<str>>(pat: S, data: &[u8]) -> AnyResult<Vec<usize>> {
-    let mut pat_bytes: Vec<u8> = Vec::<u8>::new();
-
-    for pair in pat.as_ref().split_whitespace() {
-        if pair == "?" || pair == "??" || pair == "*" || pair == "**" {
-            pat_bytes.push(0);
-        } else {
-            let num: u8 = u8::from_str_radix(pair, 16)?;
-
-            pat_bytes.push(num);
-        }
-    }
-
-    let data_len = data.len();
-    let pat_bytes_len = pat_bytes.len();
-
-    let mut skip_table = [pat_bytes.len(); 256];
-
-    for (i, byte) in pat_bytes.iter().enumerate().take(pat_bytes.len() - 1).rev() {
-        if skip_table[*byte as usize] == pat_bytes.len() {
-            skip_table[*byte as usize] = pat_bytes.len() - 1 - i;
-        }
-    }
-
-    let mut i = pat_bytes_len - 1;
-
-    let mut offset_array = Vec::<usize>::new();
-
-    while i < data_len {
-        let mut j = pat_bytes_len - 1;
-        let mut k = i;
+#[doc = r"Return value: `Vec<Offset>`
 
-        while j > 0 && (data[k] == pat_bytes[j] || pat_bytes[j] == 0) {
-            k -= 1;
-            j -= 1;
-        }
-
-        if j == 0 && (data[k] == pat_bytes[j] || pat_bytes[j] == 0) {
-            offset_array.push(k);
-        }
-
-        i += skip_table[data[i] as usize];
-    }
+Accepts the extended syntax of `pattern::ExtPattern`"]
+pub unsafe fn pat_scan<S: AsRef<str>>(pat: S, data: &[u8]) -> AnyResult<Vec<usize>> {
+    Ok(crate::pattern::ExtPattern::parse(pat)?.scan(data))
+}
 
-    Ok(offset_array)
+#[doc = r"Return value: `Lazy iterator over every offset`
+
+`vcheat::pat_scan_iter(pat, data)?.start(offset).nth(2)` = third match after `offset`"]
+pub unsafe fn pat_scan_iter<S: AsRef<str>>(
+    pat: S,
+    data: &[u8],
+) -> AnyResult<crate::pattern::Matches<'static, '_>> {
+    Ok(crate::pattern::Matches::new(
+        ::std::borrow::Cow::Owned(crate::pattern::ExtPattern::parse(pat)?),
+        data,
+    ))
 }
 
 #[doc = "Return value: `Vec<u8>`"]
+#[cfg(windows)]
 pub unsafe fn read_mem(
     proc_handle: HANDLE,
     addr: *const ::core::ffi::c_void,
@@ -136,6 +100,7 @@ pub unsafe fn read_mem(
 }
 
 #[doc = "Return value: `Bytes num written`"]
+#[cfg(windows)]
 pub unsafe fn write_mem<T>(
     proc_handle: HANDLE,
     addr: *const ::core::ffi::c_void,
@@ -159,6 +124,7 @@ pub unsafe fn write_mem<T>(
 #[doc = r#"Return value: `Bytes num written`
 
 `hex_str: "0A 1B 2C 3D 4E 5F FF"`"#]
+#[cfg(windows)]
 pub unsafe fn write_mem_hex_str<S: AsRef<str>>(
     proc_handle: HANDLE,
     addr: *const ::core::ffi::c_void,
@@ -190,6 +156,7 @@ pub unsafe fn write_mem_hex_str<S: AsRef<str>>(
 #[doc = r#"Return value: `Bytes num read`
 
 **Supports Generics**"#]
+#[cfg(windows)]
 pub unsafe fn read_mem_t<T>(
     proc_handle: HANDLE,
     addr: *const ::core::ffi::c_void,
@@ -209,6 +176,7 @@ pub unsafe fn read_mem_t<T>(
 #[doc = r#"Return value: `Bytes num written`
 
 **Supports Generics**"#]
+#[cfg(windows)]
 pub unsafe fn write_mem_t<T>(
     proc_handle: HANDLE,
     addr: *const ::core::ffi::c_void,
@@ -230,6 +198,85 @@ pub unsafe fn write_mem_t<T>(
     Ok(bytes_num_written)
 }
 
+#[doc = "Return value: `Vec<u8>`"]
+#[cfg(target_os = "linux")]
+pub unsafe fn read_mem(
+    proc_handle: HANDLE,
+    addr: *const ::core::ffi::c_void,
+    size: usize,
+) -> AnyResult<Vec<u8>> {
+    let mut buf: Vec<u8> = vec![0; size];
+
+    crate::linux::read(
+        proc_handle as i32,
+        addr as usize,
+        buf.as_mut_ptr().cast(),
+        size,
+    )?;
+
+    Ok(buf)
+}
+
+#[doc = "Return value: `Bytes num written`"]
+#[cfg(target_os = "linux")]
+pub unsafe fn write_mem<T>(
+    proc_handle: HANDLE,
+    addr: *const ::core::ffi::c_void,
+    buf: &[T],
+) -> AnyResult<usize> {
+    crate::linux::write(
+        proc_handle as i32,
+        addr as usize,
+        buf.as_ptr().cast(),
+        ::core::mem::size_of_val(buf),
+    )
+}
+
+#[doc = r#"Return value: `Bytes num written`
+
+`hex_str: "0A 1B 2C 3D 4E 5F FF"`"#]
+#[cfg(target_os = "linux")]
+pub unsafe fn write_mem_hex_str<S: AsRef<str>>(
+    proc_handle: HANDLE,
+    addr: *const ::core::ffi::c_void,
+    hex_str: S,
+) -> AnyResult<usize> {
+    let mut bytes: Vec<u8> = Vec::<u8>::new();
+
+    for c in hex_str.as_ref().split_whitespace() {
+        bytes.push(u8::from_str_radix(c, 16)?);
+    }
+
+    write_mem(proc_handle, addr, &bytes)
+}
+
+#[doc = r#"Return value: `Bytes num read`
+
+**Supports Generics**"#]
+#[cfg(target_os = "linux")]
+pub unsafe fn read_mem_t<T>(
+    proc_handle: HANDLE,
+    addr: *const ::core::ffi::c_void,
+    buf: *mut T,
+    size: usize,
+) -> AnyResult<usize> {
+    crate::linux::read(proc_handle as i32, addr as usize, buf.cast(), size)
+}
+
+#[doc = r#"Return value: `Bytes num written`
+
+**Supports Generics**"#]
+#[cfg(target_os = "linux")]
+pub unsafe fn write_mem_t<T>(
+    proc_handle: HANDLE,
+    addr: *const ::core::ffi::c_void,
+    buf: *const T,
+    size: usize,
+) -> AnyResult<usize> {
+    crate::linux::write(proc_handle as i32, addr as usize, buf.cast(), size)
+}
+
+#[cfg(windows)]
 pub unsafe fn alloc_console() -> AnyResult<()> {
     if 0 == ffi::AllocConsole() {
         return Err(::std::io::Error::last_os_error().into());
@@ -238,6 +285,7 @@ pub unsafe fn alloc_console() -> AnyResult<()> {
     Ok(())
 }
 
+#[cfg(windows)]
 pub unsafe fn free_console() -> AnyResult<()> {
     if 0 == ffi::FreeConsole() {
         return Err(::std::io::Error::last_os_error().into());
@@ -247,6 +295,7 @@ pub unsafe fn free_console() -> AnyResult<()> {
 }
 
 #[doc = "Make the console support **colored characters**"]
+#[cfg(windows)]
 pub unsafe fn colored_console() -> AnyResult<()> {
     let handle: HANDLE = ffi::GetStdHandle(0xFFFFFFF5);
 
@@ -267,6 +316,7 @@ pub unsafe fn colored_console() -> AnyResult<()> {
     Ok(())
 }
 
+#[cfg(windows)]
 pub unsafe fn get_proc_address<S: AsRef<str>>(
     mod_handle: HANDLE,
     proc_name: S,
@@ -0,0 +1,614 @@
+use crate::AnyResult;
+
+// Return value: `Bytes num read`, fails unless all of them were read
+pub(crate) unsafe fn read(
+    pid: i32,
+    addr: usize,
+    buf: *mut ::core::ffi::c_void,
+    size: usize,
+) -> AnyResult<usize> {
+    if 0 == size {
+        return Ok(0);
+    }
+
+    let local = crate::ffi::IoVec {
+        iov_base: buf,
+        iov_len: size,
+    };
+
+    let remote = crate::ffi::IoVec {
+        iov_base: addr as *mut ::core::ffi::c_void,
+        iov_len: size,
+    };
+
+    let bytes_num_read = crate::ffi::process_vm_readv(pid, &local, 1, &remote, 1, 0);
+
+    if bytes_num_read < 0 {
+        return Err(::std::io::Error::last_os_error().into());
+    }
+
+    if bytes_num_read as usize != size {
+        return Err(format!(
+            "partial read: {:#X} of {:#X} bytes at {:#X}",
+            bytes_num_read, size, addr
+        )
+        .into());
+    }
+
+    Ok(size)
+}
+
+// `process_vm_readv` takes at most `IOV_MAX` iovecs
+const IOV_MAX: usize = 1024;
+
+// Return value: `Vec<Result>`, one per request
+//
+// One `process_vm_readv` per `IOV_MAX` requests. It stops at the first remote iovec it can't read completely,
+// that request is retried alone for its error and the batch goes on after it
+pub(crate) unsafe fn read_batch(
+    pid: i32,
+    requests: &mut [crate::memory::ReadRequest<'_>],
+) -> Vec<AnyResult<()>> {
+    let mut results: Vec<AnyResult<()>> = Vec::with_capacity(requests.len());
+
+    while results.len() < requests.len() {
+        let start = results.len();
+        let end = (start + IOV_MAX).min(requests.len());
+
+        let pending = &mut requests[start..end];
+
+        let (local, remote): (Vec<crate::ffi::IoVec>, Vec<crate::ffi::IoVec>) = pending
+            .iter_mut()
+            .map(|request| {
+                (
+                    crate::ffi::IoVec {
+                        iov_base: request.buf.as_mut_ptr().cast(),
+                        iov_len: request.buf.len(),
+                    },
+                    crate::ffi::IoVec {
+                        iov_base: request.addr as *mut ::core::ffi::c_void,
+                        iov_len: request.buf.len(),
+                    },
+                )
+            })
+            .unzip();
+
+        let bytes_num_read = crate::ffi::process_vm_readv(
+            pid,
+            local.as_ptr(),
+            local.len() as ::core::ffi::c_ulong,
+            remote.as_ptr(),
+            remote.len() as ::core::ffi::c_ulong,
+            0,
+        );
+
+        let mut bytes_left = bytes_num_read.max(0) as usize;
+
+        let mut completed = 0;
+
+        while completed < pending.len() && pending[completed].buf.len() <= bytes_left {
+            bytes_left -= pending[completed].buf.len();
+            completed += 1;
+        }
+
+        results.extend((0..completed).map(|_| Ok(())));
+
+        if completed < pending.len() {
+            let request = &mut pending[completed];
+
+            results.push(
+                read(
+                    pid,
+                    request.addr,
+                    request.buf.as_mut_ptr().cast(),
+                    request.buf.len(),
+                )
+                .map(|_| ()),
+            );
+        }
+    }
+
+    results
+}
+
+// Return value: `Bytes num written`
+//
+// `process_vm_writev` honours page protections, whatever it couldn't write goes through `/proc/<pid>/mem`, which doesn't
+pub(crate) unsafe fn write(
+    pid: i32,
+    addr: usize,
+    buf: *const ::core::ffi::c_void,
+    size: usize,
+) -> AnyResult<usize> {
+    if 0 == size {
+        return Ok(0);
+    }
+
+    let local = crate::ffi::IoVec {
+        iov_base: buf.cast_mut(),
+        iov_len: size,
+    };
+
+    let remote = crate::ffi::IoVec {
+        iov_base: addr as *mut ::core::ffi::c_void,
+        iov_len: size,
+    };
+
+    let bytes_num_written = match crate::ffi::process_vm_writev(pid, &local, 1, &remote, 1, 0) {
+        n if n >= 0 => n as usize,
+        _ => {
+            let err = ::std::io::Error::last_os_error();
+
+            // EFAULT
+            if err.raw_os_error() != Some(14) {
+                return Err(err.into());
+            }
+
+            0
+        }
+    };
+
+    if bytes_num_written == size {
+        return Ok(size);
+    }
+
+    let mem = ::std::fs::OpenOptions::new()
+        .write(true)
+        .open(format!("/proc/{}/mem", pid))?;
+
+    ::std::os::unix::fs::FileExt::write_all_at(
+        &mem,
+        ::core::slice::from_raw_parts(
+            buf.cast::<u8>().add(bytes_num_written),
+            size - bytes_num_written,
+        ),
+        (addr + bytes_num_written) as u64,
+    )?;
+
+    Ok(size)
+}
+
+// Return value: `Bytes num written`, fails unless all of them were written
+//
+// `process_vm_writev` only, so read-only pages are an error rather than written through `/proc/<pid>/mem`
+pub(crate) unsafe fn write_checked(
+    pid: i32,
+    addr: usize,
+    buf: *const ::core::ffi::c_void,
+    size: usize,
+) -> AnyResult<usize> {
+    if 0 == size {
+        return Ok(0);
+    }
+
+    let local = crate::ffi::IoVec {
+        iov_base: buf.cast_mut(),
+        iov_len: size,
+    };
+
+    let remote = crate::ffi::IoVec {
+        iov_base: addr as *mut ::core::ffi::c_void,
+        iov_len: size,
+    };
+
+    let bytes_num_written = crate::ffi::process_vm_writev(pid, &local, 1, &remote, 1, 0);
+
+    if bytes_num_written < 0 {
+        return Err(::std::io::Error::last_os_error().into());
+    }
+
+    if bytes_num_written as usize != size {
+        return Err(format!(
+            "partial write: {:#X} of {:#X} bytes at {:#X}",
+            bytes_num_written, size, addr
+        )
+        .into());
+    }
+
+    Ok(size)
+}
+
+// Return value: `ProcInfo`, `None` if the process is gone
+pub(crate) fn proc_info(pid: u32) -> Option<crate::types::ProcInfo> {
+    let dir = format!("/proc/{}", pid);
+
+    let comm = ::std::fs::read_to_string(format!("{}/comm", dir)).ok()?;
+    let comm = comm.trim_end_matches('\n').to_owned();
+
+    // Unreadable for processes of other users
+    let exe = ::std::fs::read_link(format!("{}/exe", dir))
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let cmdline = cmdline(pid);
+
+    // `comm` is cut at 15 bytes
+    let name = wine_exe_name(&cmdline)
+        .or_else(|| exe.rsplit('/').next().filter(|name| !name.is_empty()))
+        .unwrap_or(&comm)
+        .to_owned();
+
+    Some(crate::types::ProcInfo {
+        name,
+        id: pid,
+        comm,
+        exe,
+        cmdline,
+    })
+}
+
+// Empty if unreadable
+fn cmdline(pid: u32) -> Vec<String> {
+    ::std::fs::read(format!("/proc/{}/cmdline", pid))
+        .unwrap_or_default()
+        .split(|byte| *byte == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect()
+}
+
+pub(crate) fn all_proc_info() -> AnyResult<Vec<crate::types::ProcInfo>> {
+    let mut pids: Vec<u32> = ::std::fs::read_dir("/proc")?
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+        .collect();
+
+    pids.sort_unstable();
+
+    Ok(pids.into_iter().filter_map(proc_info).collect())
+}
+
+// Return value: `Windows exe name` of a Wine/Proton process
+//
+// `C:\path\game.exe ...`, or `wine64-preloader Z:\path\game.exe ...`
+pub(crate) fn wine_exe_name(cmdline: &[String]) -> Option<&str> {
+    fn base_name(arg: &str) -> &str {
+        arg.rsplit(['\\', '/']).next().unwrap_or(arg)
+    }
+
+    let first = base_name(cmdline.first()?);
+
+    let exe = if first.to_ascii_lowercase().starts_with("wine") {
+        base_name(cmdline.get(1)?)
+    } else {
+        first
+    };
+
+    exe.to_ascii_lowercase().ends_with(".exe").then_some(exe)
+}
+
+// One line of `/proc/<pid>/maps`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MapsEntry {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    // `rwxp`/`rwxs`
+    pub(crate) perms: [u8; 4],
+    pub(crate) offset: u64,
+    pub(crate) inode: u64,
+    // Empty for anonymous mappings, `[heap]`, `[stack]`, ...
+    pub(crate) path: String,
+}
+
+pub(crate) fn maps(pid: u32) -> AnyResult<Vec<MapsEntry>> {
+    ::std::fs::read_to_string(format!("/proc/{}/maps", pid))?
+        .lines()
+        .map(parse_maps_line)
+        .collect()
+}
+
+fn parse_maps_line(line: &str) -> AnyResult<MapsEntry> {
+    let invalid = || format!("invalid maps line: {}", line);
+
+    // `start-end perms offset dev inode     path`
+    let mut fields = line.splitn(6, ' ');
+
+    let mut field = || fields.next().ok_or_else(invalid);
+
+    let (start, end) = field()?.split_once('-').ok_or_else(invalid)?;
+
+    let perms: [u8; 4] = field()?.as_bytes().try_into().map_err(|_| invalid())?;
+
+    let offset = u64::from_str_radix(field()?, 16)?;
+
+    let _dev = field()?;
+
+    let inode = field()?.parse()?;
+
+    let path = fields.next().unwrap_or_default().trim_start();
+
+    Ok(MapsEntry {
+        start: usize::from_str_radix(start, 16)?,
+        end: usize::from_str_radix(end, 16)?,
+        perms,
+        offset,
+        inode,
+        path: path.strip_suffix(" (deleted)").unwrap_or(path).to_owned(),
+    })
+}
+
+// Return value: `Vec<ModInfo>`, in address order
+//
+// Consecutive mappings of one file form a module; a PE image mapped by Wine spans its `SizeOfImage`
+pub(crate) fn all_mod_info(pid: u32) -> AnyResult<Vec<crate::types::ModInfo>> {
+    let mut mods_info: Vec<crate::types::ModInfo> = Vec::new();
+
+    let mut current: Option<(String, usize, usize)> = None;
+
+    let mut push = |(path, start, end): (String, usize, usize)| {
+        let name = path.rsplit('/').next().unwrap_or(&path).to_owned();
+
+        let size = pe_size_of_image(pid, start).unwrap_or(end - start);
+
+        mods_info.push(crate::types::ModInfo {
+            name,
+            handle: start as crate::HANDLE,
+            addr: start as *mut ::core::ffi::c_void,
+            size: size.min(u32::MAX as usize) as u32,
+        });
+    };
+
+    for entry in maps(pid)? {
+        // Anonymous mappings (.bss, Wine's section gaps) don't split a module
+        if entry.inode == 0 || !entry.path.starts_with('/') {
+            continue;
+        }
+
+        match current {
+            Some((ref path, _, ref mut end)) if *path == entry.path => *end = entry.end,
+            _ => {
+                if let Some(module) = current.take() {
+                    push(module);
+                }
+
+                current = Some((entry.path, entry.start, entry.end));
+            }
+        }
+    }
+
+    if let Some(module) = current.take() {
+        push(module);
+    }
+
+    Ok(mods_info)
+}
+
+// Return value: `SizeOfImage` of the PE image at `base`
+fn pe_size_of_image(pid: u32, base: usize) -> Option<usize> {
+    let nt_headers = pe_nt_headers(pid, base)?;
+
+    Some(u32::from_le_bytes(nt_headers[0x50..0x54].try_into().ok()?) as usize)
+}
+
+// Signature, file header, optional header up to `SizeOfImage`
+fn pe_nt_headers(pid: u32, base: usize) -> Option<[u8; 0x54]> {
+    let mut dos_header = [0u8; 0x40];
+
+    unsafe { read(pid as i32, base, dos_header.as_mut_ptr().cast(), 0x40).ok()? };
+
+    if &dos_header[..2] != b"MZ" {
+        return None;
+    }
+
+    let e_lfanew = u32::from_le_bytes(dos_header[0x3C..0x40].try_into().ok()?) as usize;
+
+    let mut nt_headers = [0u8; 0x54];
+
+    unsafe {
+        read(
+            pid as i32,
+            base.checked_add(e_lfanew)?,
+            nt_headers.as_mut_ptr().cast(),
+            0x54,
+        )
+        .ok()?
+    };
+
+    if &nt_headers[..4] != b"PE\0\0" {
+        return None;
+    }
+
+    Some(nt_headers)
+}
+
+// Return value: `PointerWidth` of the Wine PE image if there is one, else the ELF class of the executable
+pub(crate) fn pointer_width(pid: u32) -> AnyResult<crate::memory::PointerWidth> {
+    use crate::memory::PointerWidth;
+
+    let cmdline = cmdline(pid);
+
+    let pe_width = wine_exe_name(&cmdline).and_then(|name| {
+        // Headers of the `.exe` only, the first mapping of the file
+        let base = maps(pid)
+            .ok()?
+            .into_iter()
+            .find(|entry| {
+                entry.inode != 0
+                    && entry.offset == 0
+                    && entry
+                        .path
+                        .rsplit('/')
+                        .next()
+                        .is_some_and(|file_name| file_name.eq_ignore_ascii_case(name))
+            })?
+            .start;
+
+        let nt_headers = pe_nt_headers(pid, base)?;
+
+        // IMAGE_FILE_HEADER::Machine
+        match u16::from_le_bytes([nt_headers[4], nt_headers[5]]) {
+            0x014C | 0x01C4 => Some(PointerWidth::Bits32),
+            0x8664 | 0xAA64 => Some(PointerWidth::Bits64),
+            _ => None,
+        }
+    });
+
+    if let Some(width) = pe_width {
+        return Ok(width);
+    }
+
+    let mut ident = [0u8; 5];
+
+    ::std::io::Read::read_exact(
+        &mut ::std::fs::File::open(format!("/proc/{}/exe", pid))?,
+        &mut ident,
+    )?;
+
+    if &ident[..4] != b"\x7FELF" {
+        return Err(format!("/proc/{}/exe is not an ELF file", pid).into());
+    }
+
+    match ident[4] {
+        1 => Ok(PointerWidth::Bits32),
+        2 => Ok(PointerWidth::Bits64),
+        class => Err(format!("unknown ELF class: {}", class).into()),
+    }
+}
+
+// Return value: `mem_protect` of `rwx` flags
+fn protect(read: bool, write: bool, exec: bool) -> u32 {
+    use crate::types::mem_protect::*;
+
+    match (read, write, exec) {
+        (_, true, true) => EXECUTE_READ_WRITE,
+        (true, false, true) => EXECUTE_READ,
+        (false, false, true) => EXECUTE,
+        (_, true, false) => READ_WRITE,
+        (true, false, false) => READONLY,
+        (false, false, false) => NOACCESS,
+    }
+}
+
+// `---` mappings stand in for reserved memory
+fn state(protect: u32) -> u32 {
+    if protect == crate::types::mem_protect::NOACCESS {
+        crate::types::mem_alloc::RESERVE
+    } else {
+        crate::types::mem_alloc::COMMIT
+    }
+}
+
+// Return value: `Vec<MemRegion>`, from `smaps` for the maximum protection, `maps` if it can't be read
+pub(crate) fn regions(pid: u32) -> AnyResult<Vec<crate::types::MemRegion>> {
+    // (Entry, `VmFlags`)
+    let mut entries: Vec<(MapsEntry, Option<String>)> = Vec::new();
+
+    match ::std::fs::read_to_string(format!("/proc/{}/smaps", pid)) {
+        Ok(smaps) => {
+            for line in smaps.lines() {
+                match line.split_once(':') {
+                    Some(("VmFlags", flags)) => {
+                        if let Some((_, vm_flags)) = entries.last_mut() {
+                            *vm_flags = Some(flags.trim().to_owned());
+                        }
+                    }
+                    // `Size:  4 kB`, ...
+                    Some((key, _)) if !key.contains(' ') => {}
+                    _ => entries.push((parse_maps_line(line)?, None)),
+                }
+            }
+        }
+        Err(_) => entries.extend(maps(pid)?.into_iter().map(|entry| (entry, None))),
+    }
+
+    let executable_paths: ::std::collections::HashSet<&str> = entries
+        .iter()
+        .filter(|(entry, _)| entry.perms[2] == b'x' && entry.inode != 0)
+        .map(|(entry, _)| entry.path.as_str())
+        .collect();
+
+    let mut regions: Vec<crate::types::MemRegion> = Vec::new();
+
+    // (Path, base) of the module being walked, like `all_mod_info`
+    let mut module: Option<(&str, usize)> = None;
+
+    for (entry, vm_flags) in &entries {
+        let file_backed = entry.inode != 0 && entry.path.starts_with('/');
+
+        let type_ = if !file_backed {
+            crate::types::mem_type::PRIVATE
+        } else if executable_paths.contains(entry.path.as_str()) {
+            crate::types::mem_type::IMAGE
+        } else {
+            crate::types::mem_type::MAPPED
+        };
+
+        let mut alloc_base = entry.start;
+
+        if file_backed {
+            match module {
+                Some((path, base)) if path == entry.path => alloc_base = base,
+                _ => module = Some((&entry.path, entry.start)),
+            }
+        }
+
+        let protect = protect(
+            entry.perms[0] == b'r',
+            entry.perms[1] == b'w',
+            entry.perms[2] == b'x',
+        );
+
+        let alloc_protect = match vm_flags {
+            Some(flags) => {
+                let has = |flag: &str| flags.split_whitespace().any(|f| f == flag);
+
+                self::protect(has("mr"), has("mw"), has("me"))
+            }
+            None => protect,
+        };
+
+        regions.push(crate::types::MemRegion {
+            base: entry.start,
+            size: entry.end - entry.start,
+            alloc_base,
+            alloc_protect,
+            protect,
+            state: state(protect),
+            type_,
+            path: entry.path.clone(),
+        });
+    }
+
+    Ok(regions)
+}
+
+// Return value: `MemInfo` like `VirtualQueryEx`, `region_size` counts from the page of `addr`
+//
+// Reads `maps` only up to the mapping of `addr`, the fields needed are all on that line
+pub(crate) fn query(pid: u32, addr: usize) -> AnyResult<crate::types::MemInfo> {
+    let maps = ::std::fs::File::open(format!("/proc/{}/maps", pid))?;
+
+    for line in ::std::io::BufRead::lines(::std::io::BufReader::new(maps)) {
+        let line = line?;
+
+        let (start, end) = line
+            .split(' ')
+            .next()
+            .and_then(|range| range.split_once('-'))
+            .ok_or_else(|| format!("invalid maps line: {}", line))?;
+
+        // Sorted by address
+        if addr < usize::from_str_radix(start, 16)? {
+            break;
+        }
+
+        if addr >= usize::from_str_radix(end, 16)? {
+            continue;
+        }
+
+        let entry = parse_maps_line(&line)?;
+
+        let protect = protect(
+            entry.perms[0] == b'r',
+            entry.perms[1] == b'w',
+            entry.perms[2] == b'x',
+        );
+
+        return Ok(crate::types::MemInfo {
+            protect,
+            state: state(protect),
+            region_size: entry.end - (addr & !0xFFF),
+        });
+    }
+
+    Err(format!("{:#X} is not mapped", addr).into())
+}
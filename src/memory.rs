@@ -0,0 +1,664 @@
+use crate::AnyResult;
+
+mod cache;
+mod chain;
+mod ptr;
+
+pub use self::cache::{CacheStats, CachedMemory};
+pub use self::chain::{PointerChainError, PointerWidth};
+pub use self::ptr::{RemotePtr, RemotePtr32};
+
+const PAGE_SIZE: usize = 0x1000;
+
+#[doc = r"Plain old data: `Copy`, no padding, and every bit pattern is a valid value
+
+`unsafe impl Pod for MyStruct {}` on a `#[repr(C)]` struct whose fields are `Pod` and leave no padding"]
+pub unsafe trait Pod: Copy + 'static {
+    #[inline]
+    fn zeroed() -> Self {
+        unsafe { ::core::mem::zeroed() }
+    }
+}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+#[inline]
+pub(crate) fn bytes_of<T: Pod>(value: &T) -> &[u8] {
+    unsafe {
+        ::core::slice::from_raw_parts((value as *const T).cast(), ::core::mem::size_of::<T>())
+    }
+}
+
+#[inline]
+pub(crate) fn bytes_of_mut<T: Pod>(value: &mut T) -> &mut [u8] {
+    unsafe {
+        ::core::slice::from_raw_parts_mut((value as *mut T).cast(), ::core::mem::size_of::<T>())
+    }
+}
+
+#[inline]
+pub(crate) fn slice_bytes_of_mut<T: Pod>(values: &mut [T]) -> &mut [u8] {
+    unsafe {
+        ::core::slice::from_raw_parts_mut(
+            values.as_mut_ptr().cast(),
+            ::core::mem::size_of_val(values),
+        )
+    }
+}
+
+#[doc = "One read of `MemoryAccess::read_batch`: fill `buf` from `addr`"]
+#[derive(Debug)]
+pub struct ReadRequest<'a> {
+    pub addr: usize,
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> ReadRequest<'a> {
+    pub fn new(addr: usize, buf: &'a mut [u8]) -> Self {
+        Self { addr, buf }
+    }
+}
+
+#[doc = r"Read, write and query memory of some address space
+
+`read_bytes` and `write_bytes` transfer the whole buffer or fail"]
+pub trait MemoryAccess {
+    fn read_bytes(&self, addr: usize, buf: &mut [u8]) -> AnyResult<()>;
+
+    fn write_bytes(&self, addr: usize, buf: &[u8]) -> AnyResult<()>;
+
+    #[doc = "Return value: `MemInfo` of the region containing `addr`"]
+    fn query(&self, addr: usize) -> AnyResult<crate::types::MemInfo>;
+
+    #[doc = r"Return value: `Vec<Result>`, one per request
+
+A failing request doesn't affect the others. Backends override it to need fewer syscalls"]
+    fn read_batch(&self, requests: &mut [ReadRequest<'_>]) -> Vec<AnyResult<()>> {
+        requests
+            .iter_mut()
+            .map(|request| self.read_bytes(request.addr, request.buf))
+            .collect()
+    }
+
+    #[doc = "Return value: `Vec<u8>`"]
+    fn read_vec(&self, addr: usize, size: usize) -> AnyResult<Vec<u8>> {
+        let mut buf: Vec<u8> = vec![0; size];
+
+        self.read_bytes(addr, &mut buf)?;
+
+        Ok(buf)
+    }
+
+    #[doc = "Return value: `Pointer size of the target`, this build's by default"]
+    fn pointer_width(&self) -> PointerWidth {
+        PointerWidth::native()
+    }
+
+    #[doc = "Return value: `Pointer at addr`, `pointer_width()` bytes"]
+    fn read_ptr(&self, addr: usize) -> AnyResult<usize> {
+        self.read_ptr_width(addr, self.pointer_width())
+    }
+
+    #[doc = "Return value: `Pointer of the given width at addr`, zero-extended"]
+    fn read_ptr_width(&self, addr: usize, width: PointerWidth) -> AnyResult<usize> {
+        match width {
+            PointerWidth::Bits32 => {
+                let mut buf = [0u8; 4];
+
+                self.read_bytes(addr, &mut buf)?;
+
+                Ok(u32::from_ne_bytes(buf) as usize)
+            }
+            PointerWidth::Bits64 => {
+                let mut buf = [0u8; 8];
+
+                self.read_bytes(addr, &mut buf)?;
+
+                Ok(usize::try_from(u64::from_ne_bytes(buf))?)
+            }
+        }
+    }
+
+    #[doc = "Return value: `T`"]
+    fn read<T: Pod>(&self, addr: usize) -> AnyResult<T>
+    where
+        Self: Sized,
+    {
+        let mut value: T = T::zeroed();
+
+        self.read_bytes(addr, bytes_of_mut(&mut value))?;
+
+        Ok(value)
+    }
+
+    #[doc = "Return value: `Vec<T>` of `n` consecutive values"]
+    fn read_slice<T: Pod>(&self, addr: usize, n: usize) -> AnyResult<Vec<T>>
+    where
+        Self: Sized,
+    {
+        if n.checked_mul(::core::mem::size_of::<T>()).is_none() {
+            return Err(format!("{} values overflow the address space", n).into());
+        }
+
+        let mut values: Vec<T> = vec![T::zeroed(); n];
+
+        self.read_bytes(addr, slice_bytes_of_mut(&mut values))?;
+
+        Ok(values)
+    }
+
+    fn write<T: Pod>(&self, addr: usize, value: &T) -> AnyResult<()>
+    where
+        Self: Sized,
+    {
+        self.write_bytes(addr, bytes_of(value))
+    }
+
+    #[doc = r"Return value: `Final pointer`
+
+Dereferences `base`, then for every offset adds it and dereferences again, with pointers of `pointer_width()`.
+The error is a `PointerChainError`"]
+    fn read_multi_pointer(&self, base: usize, byte_offsets: &[isize]) -> AnyResult<usize> {
+        Ok(self.read_pointer_chain(base, byte_offsets, self.pointer_width())?)
+    }
+
+    #[doc = r"Return value: `Final pointer`
+
+Same as `read_multi_pointer`, with pointers of `width` bytes and addresses wrapping at that width"]
+    fn read_pointer_chain(
+        &self,
+        base: usize,
+        byte_offsets: &[isize],
+        width: PointerWidth,
+    ) -> Result<usize, PointerChainError> {
+        chain::read_chain(self, base, byte_offsets, width)
+    }
+
+    #[doc = r"Return value: `String` up to the NUL, or of `max_len` bytes if there is none before
+
+Reads page by page, so the string may end right before an unreadable page"]
+    fn read_c_str(&self, addr: usize, max_len: usize) -> AnyResult<String> {
+        Ok(String::from_utf8(read_until_nul(self, addr, max_len, 1)?)?)
+    }
+
+    #[doc = r"Return value: `String` up to the NUL, or of `max_len` UTF-16 units if there is none before
+
+Reads page by page, so the string may end right before an unreadable page"]
+    fn read_c_str_utf16(&self, addr: usize, max_len: usize) -> AnyResult<String> {
+        let bytes = read_until_nul(
+            self,
+            addr,
+            max_len.checked_mul(2).ok_or("\"max_len\" is too large")?,
+            2,
+        )?;
+
+        Ok(String::from_utf16(
+            &bytes
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect::<Vec<u16>>(),
+        )?)
+    }
+
+    #[doc = "Return value: `String` of exactly `len` bytes"]
+    fn read_str(&self, addr: usize, len: usize) -> AnyResult<String> {
+        Ok(String::from_utf8(self.read_vec(addr, len)?)?)
+    }
+
+    #[doc = "Return value: `String` of exactly `len` UTF-16 units"]
+    fn read_str_utf16(&self, addr: usize, len: usize) -> AnyResult<String> {
+        let bytes = self.read_vec(addr, len.checked_mul(2).ok_or("\"len\" is too large")?)?;
+
+        Ok(String::from_utf16(
+            &bytes
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect::<Vec<u16>>(),
+        )?)
+    }
+
+    #[doc = r"Writes `s` and its NUL
+
+Fails without writing if both need more than `capacity` bytes or `s` contains a NUL"]
+    fn write_c_str(&self, addr: usize, s: &str, capacity: usize) -> AnyResult<()> {
+        if s.contains('\0') {
+            return Err("\"s\" contains a NUL".into());
+        }
+
+        if s.len() + 1 > capacity {
+            return Err(format!("{} bytes don't fit into {}", s.len() + 1, capacity).into());
+        }
+
+        let mut bytes: Vec<u8> = Vec::with_capacity(s.len() + 1);
+
+        bytes.extend_from_slice(s.as_bytes());
+        bytes.push(0);
+
+        self.write_bytes(addr, &bytes)
+    }
+
+    #[doc = r"Writes `s` as UTF-16LE and its NUL
+
+Fails without writing if both need more than `capacity` UTF-16 units or `s` contains a NUL"]
+    fn write_c_str_utf16(&self, addr: usize, s: &str, capacity: usize) -> AnyResult<()> {
+        if s.contains('\0') {
+            return Err("\"s\" contains a NUL".into());
+        }
+
+        let bytes: Vec<u8> = s
+            .encode_utf16()
+            .chain(Some(0))
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+
+        if bytes.len() / 2 > capacity {
+            return Err(format!("{} units don't fit into {}", bytes.len() / 2, capacity).into());
+        }
+
+        self.write_bytes(addr, &bytes)
+    }
+}
+
+// Return value: `Bytes` before the first all-zero `unit`, at most `max_len`
+//
+// Chunks end at page boundaries, nothing past the terminator's page is touched
+fn read_until_nul<M: MemoryAccess + ?Sized>(
+    mem: &M,
+    addr: usize,
+    max_len: usize,
+    unit: usize,
+) -> AnyResult<Vec<u8>> {
+    let mut bytes: Vec<u8> = Vec::new();
+
+    let mut chunk = [0u8; PAGE_SIZE];
+
+    while bytes.len() < max_len {
+        let cur = addr.wrapping_add(bytes.len());
+
+        let len = (PAGE_SIZE - cur % PAGE_SIZE).min(max_len - bytes.len());
+
+        mem.read_bytes(cur, &mut chunk[..len])?;
+
+        let searched_from = bytes.len() - bytes.len() % unit;
+
+        bytes.extend_from_slice(&chunk[..len]);
+
+        if let Some(end) = bytes[searched_from..]
+            .chunks_exact(unit)
+            .position(|unit| unit.iter().all(|byte| *byte == 0))
+        {
+            bytes.truncate(searched_from + end * unit);
+
+            return Ok(bytes);
+        }
+    }
+
+    bytes.truncate(max_len - max_len % unit);
+
+    Ok(bytes)
+}
+
+impl<M: MemoryAccess + ?Sized> MemoryAccess for &M {
+    #[inline]
+    fn read_bytes(&self, addr: usize, buf: &mut [u8]) -> AnyResult<()> {
+        (**self).read_bytes(addr, buf)
+    }
+
+    #[inline]
+    fn write_bytes(&self, addr: usize, buf: &[u8]) -> AnyResult<()> {
+        (**self).write_bytes(addr, buf)
+    }
+
+    #[inline]
+    fn query(&self, addr: usize) -> AnyResult<crate::types::MemInfo> {
+        (**self).query(addr)
+    }
+
+    #[inline]
+    fn pointer_width(&self) -> PointerWidth {
+        (**self).pointer_width()
+    }
+
+    #[inline]
+    fn read_batch(&self, requests: &mut [ReadRequest<'_>]) -> Vec<AnyResult<()>> {
+        (**self).read_batch(requests)
+    }
+}
+
+#[doc = r"Memory of the current process
+
+A wild address, e.g. one followed by `read_multi_pointer`, is an error instead of a crash:
+- Linux: `process_vm_readv`/`process_vm_writev` on the own pid, which fail with `EFAULT` on unmapped or protected pages
+- Windows: the range is checked with `query` to be committed and readable (writable for writes), then dereferenced directly.
+The check can't see another thread unmapping or reprotecting the memory right after it, ruling that out is the contract of `new`"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LocalMemory {
+    _private: (),
+}
+
+impl LocalMemory {
+    #[doc = "Memory accessed through it must not be unmapped or reprotected by other threads while it is being accessed"]
+    pub unsafe fn new() -> Self {
+        Self { _private: () }
+    }
+
+    // Return value: `Ok` if `addr..addr + size` is committed and allows the access
+    #[cfg(not(target_os = "linux"))]
+    fn check(&self, addr: usize, size: usize, write: bool) -> AnyResult<()> {
+        use crate::types::mem_protect::*;
+
+        let allowed = match write {
+            true => READ_WRITE | WRITECOPY | EXECUTE_READ_WRITE | EXECUTE_WRITECOPY,
+            false => {
+                READONLY
+                    | READ_WRITE
+                    | WRITECOPY
+                    | EXECUTE_READ
+                    | EXECUTE_READ_WRITE
+                    | EXECUTE_WRITECOPY
+            }
+        };
+
+        if 0 == addr {
+            return Err("null pointer".into());
+        }
+
+        let end = addr
+            .checked_add(size)
+            .ok_or_else(|| format!("{:#X}..+{:#X} overflows", addr, size))?;
+
+        let mut page = addr & !(PAGE_SIZE - 1);
+
+        while page < end {
+            let mi = self.query(page.max(addr))?;
+
+            if mi.state != crate::types::mem_alloc::COMMIT
+                || mi.protect & GUARD != 0
+                || mi.protect & allowed == 0
+            {
+                return Err(format!(
+                    "{:#X} is not {}",
+                    page.max(addr),
+                    if write { "writable" } else { "readable" }
+                )
+                .into());
+            }
+
+            page = match page.checked_add(mi.region_size.max(PAGE_SIZE)) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+
+        Ok(())
+    }
+}
+
+impl MemoryAccess for LocalMemory {
+    #[cfg(target_os = "linux")]
+    fn read_bytes(&self, addr: usize, buf: &mut [u8]) -> AnyResult<()> {
+        unsafe {
+            crate::linux::read(
+                ::std::process::id() as i32,
+                addr,
+                buf.as_mut_ptr().cast(),
+                buf.len(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read_bytes(&self, addr: usize, buf: &mut [u8]) -> AnyResult<()> {
+        self.check(addr, buf.len(), false)?;
+
+        unsafe {
+            ::core::ptr::copy_nonoverlapping(addr as *const u8, buf.as_mut_ptr(), buf.len());
+        }
+
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn write_bytes(&self, addr: usize, buf: &[u8]) -> AnyResult<()> {
+        unsafe {
+            crate::linux::write_checked(
+                ::std::process::id() as i32,
+                addr,
+                buf.as_ptr().cast(),
+                buf.len(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn write_bytes(&self, addr: usize, buf: &[u8]) -> AnyResult<()> {
+        self.check(addr, buf.len(), true)?;
+
+        unsafe {
+            ::core::ptr::copy_nonoverlapping(buf.as_ptr(), addr as *mut u8, buf.len());
+        }
+
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn query(&self, addr: usize) -> AnyResult<crate::types::MemInfo> {
+        unsafe { crate::internal::query_mem(addr as _) }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn query(&self, addr: usize) -> AnyResult<crate::types::MemInfo> {
+        crate::linux::query(::std::process::id(), addr)
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    fn query(&self, _addr: usize) -> AnyResult<crate::types::MemInfo> {
+        Err("query is not supported on this platform".into())
+    }
+}
+
+#[doc = r"Memory of another process, through `read_mem_t`/`write_mem`
+
+`ReadProcessMemory`/`WriteProcessMemory` on Windows, `process_vm_readv`/`process_vm_writev` on Linux"]
+#[cfg(any(windows, target_os = "linux"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RemoteMemory {
+    proc_handle: crate::HANDLE,
+    pointer_width: PointerWidth,
+}
+
+#[cfg(any(windows, target_os = "linux"))]
+impl RemoteMemory {
+    #[doc = r"`proc_handle` comes from `external::open_proc`
+
+On Windows it needs `PROCESS_VM_READ`, `PROCESS_VM_WRITE`/`PROCESS_VM_OPERATION` for writes and `PROCESS_QUERY_INFORMATION` for queries.
+The pointer width is detected once here with `external::get_pointer_width`, its error is returned as is"]
+    pub fn new(proc_handle: crate::HANDLE) -> AnyResult<Self> {
+        let pointer_width = unsafe { crate::external::get_pointer_width(proc_handle)? };
+
+        Ok(Self::from_handle(proc_handle, pointer_width))
+    }
+
+    #[doc = "No detection, for handles that can't be queried or targets whose width is already known"]
+    pub const fn from_handle(proc_handle: crate::HANDLE, pointer_width: PointerWidth) -> Self {
+        Self {
+            proc_handle,
+            pointer_width,
+        }
+    }
+
+    #[doc = "Overrides the detected pointer width"]
+    pub fn with_pointer_width(mut self, pointer_width: PointerWidth) -> Self {
+        self.pointer_width = pointer_width;
+
+        self
+    }
+
+    #[inline]
+    pub fn handle(&self) -> crate::HANDLE {
+        self.proc_handle
+    }
+}
+
+#[cfg(any(windows, target_os = "linux"))]
+impl MemoryAccess for RemoteMemory {
+    fn read_bytes(&self, addr: usize, buf: &mut [u8]) -> AnyResult<()> {
+        let bytes_num_read =
+            unsafe { crate::read_mem_t(self.proc_handle, addr as _, buf.as_mut_ptr(), buf.len())? };
+
+        if bytes_num_read != buf.len() {
+            return Err(format!("partial read at {:#X}", addr).into());
+        }
+
+        Ok(())
+    }
+
+    fn write_bytes(&self, addr: usize, buf: &[u8]) -> AnyResult<()> {
+        let bytes_num_written = unsafe { crate::write_mem(self.proc_handle, addr as _, buf)? };
+
+        if bytes_num_written != buf.len() {
+            return Err(format!("partial write at {:#X}", addr).into());
+        }
+
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn query(&self, addr: usize) -> AnyResult<crate::types::MemInfo> {
+        unsafe { crate::external::query_mem(self.proc_handle, addr) }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn query(&self, addr: usize) -> AnyResult<crate::types::MemInfo> {
+        crate::linux::query(self.proc_handle as u32, addr)
+    }
+
+    #[inline]
+    fn pointer_width(&self) -> PointerWidth {
+        self.pointer_width
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read_batch(&self, requests: &mut [ReadRequest<'_>]) -> Vec<AnyResult<()>> {
+        unsafe { crate::linux::read_batch(self.proc_handle as i32, requests) }
+    }
+}
+
+#[doc = r"Byte buffer standing in for memory loaded at `base`, e.g. a module dump
+
+Every address in `base..base + len` is committed read/write memory"]
+#[derive(Debug, Default)]
+pub struct BufferMemory {
+    base: usize,
+    data: ::std::sync::RwLock<Vec<u8>>,
+    pointer_width: PointerWidth,
+}
+
+impl BufferMemory {
+    pub fn new(base: usize, data: Vec<u8>) -> Self {
+        Self {
+            base,
+            data: ::std::sync::RwLock::new(data),
+            pointer_width: PointerWidth::native(),
+        }
+    }
+
+    #[doc = "Pointer size of the process the buffer came from, this build's by default"]
+    pub fn with_pointer_width(mut self, pointer_width: PointerWidth) -> Self {
+        self.pointer_width = pointer_width;
+
+        self
+    }
+
+    #[inline]
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    pub fn len(&self) -> usize {
+        self.data
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.data
+            .into_inner()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    // Return value: `Range` of `data` backing `addr..addr + size`
+    fn range(&self, len: usize, addr: usize, size: usize) -> AnyResult<::core::ops::Range<usize>> {
+        addr.checked_sub(self.base)
+            .and_then(|start| Some(start..start.checked_add(size)?))
+            .filter(|range| range.end <= len)
+            .ok_or_else(|| format!("{:#X}..+{:#X} is outside of the buffer", addr, size).into())
+    }
+}
+
+impl Clone for BufferMemory {
+    fn clone(&self) -> Self {
+        Self::new(
+            self.base,
+            self.data
+                .read()
+                .unwrap_or_else(|err| err.into_inner())
+                .clone(),
+        )
+        .with_pointer_width(self.pointer_width)
+    }
+}
+
+impl MemoryAccess for BufferMemory {
+    fn read_bytes(&self, addr: usize, buf: &mut [u8]) -> AnyResult<()> {
+        let data = self.data.read().unwrap_or_else(|err| err.into_inner());
+
+        buf.copy_from_slice(&data[self.range(data.len(), addr, buf.len())?]);
+
+        Ok(())
+    }
+
+    fn write_bytes(&self, addr: usize, buf: &[u8]) -> AnyResult<()> {
+        let mut data = self.data.write().unwrap_or_else(|err| err.into_inner());
+
+        let range = self.range(data.len(), addr, buf.len())?;
+
+        data[range].copy_from_slice(buf);
+
+        Ok(())
+    }
+
+    fn query(&self, addr: usize) -> AnyResult<crate::types::MemInfo> {
+        let len = self.len();
+
+        let range = self.range(len, addr, 1)?;
+
+        Ok(crate::types::MemInfo {
+            protect: crate::types::mem_protect::READ_WRITE,
+            state: crate::types::mem_alloc::COMMIT,
+            region_size: len - range.start,
+        })
+    }
+
+    #[inline]
+    fn pointer_width(&self) -> PointerWidth {
+        self.pointer_width
+    }
+}
@@ -0,0 +1,296 @@
+use super::{MemoryAccess, PAGE_SIZE};
+use crate::AnyResult;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CacheStats {
+    #[doc = "Pages served from the cache"]
+    pub hits: u64,
+    #[doc = "Pages read from the wrapped memory"]
+    pub misses: u64,
+}
+
+impl CacheStats {
+    #[doc = "Return value: `hits / (hits + misses)`, 0 without any access"]
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Page {
+    data: Box<[u8; PAGE_SIZE]>,
+    loaded_at: ::std::time::Instant,
+    generation: u64,
+}
+
+// 16 MiB
+const DEFAULT_MAX_PAGES: usize = 0x1000;
+
+#[doc = r"Page cache over another `MemoryAccess`
+
+A cached page stays valid until it's older than the TTL, `next_generation` is called or it's invalidated.
+Writes go to the wrapped memory first and then update the cached pages.
+At most `max_pages` pages are kept, stale pages go first, then the oldest.
+The wrapped memory is never accessed while the cache is locked.
+
+`CachedMemory::new(RemoteMemory::new(proc_handle)?).ttl(Duration::from_millis(16))`"]
+#[derive(Debug)]
+pub struct CachedMemory<M: MemoryAccess> {
+    inner: M,
+    ttl: Option<::std::time::Duration>,
+    max_pages: usize,
+    generation: ::std::sync::atomic::AtomicU64,
+    // Bumped by writes and invalidations, a page read across a bump isn't cached
+    epoch: ::std::sync::atomic::AtomicU64,
+    pages: ::std::sync::Mutex<::std::collections::HashMap<usize, Page>>,
+    hits: ::std::sync::atomic::AtomicU64,
+    misses: ::std::sync::atomic::AtomicU64,
+}
+
+impl<M: MemoryAccess> CachedMemory<M> {
+    #[doc = "Without a TTL pages stay valid until invalidated or the generation changes"]
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            ttl: None,
+            max_pages: DEFAULT_MAX_PAGES,
+            generation: ::std::sync::atomic::AtomicU64::new(0),
+            epoch: ::std::sync::atomic::AtomicU64::new(0),
+            pages: ::std::sync::Mutex::new(::std::collections::HashMap::new()),
+            hits: ::std::sync::atomic::AtomicU64::new(0),
+            misses: ::std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    pub fn ttl(mut self, ttl: ::std::time::Duration) -> Self {
+        self.ttl = Some(ttl);
+
+        self
+    }
+
+    #[doc = "Defaults to 0x1000 pages (16 MiB)"]
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+
+        self
+    }
+
+    #[inline]
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    #[doc = r"Return value: `New generation`
+
+Every page cached so far is dropped, e.g. once per frame"]
+    pub fn next_generation(&self) -> u64 {
+        let mut pages = self.lock_pages();
+
+        pages.clear();
+
+        self.generation
+            .fetch_add(1, ::std::sync::atomic::Ordering::Relaxed)
+            + 1
+    }
+
+    #[doc = "Drops the cached pages overlapping `addr..addr + size`"]
+    pub fn invalidate(&self, addr: usize, size: usize) {
+        let mut pages = self.lock_pages();
+
+        self.epoch
+            .fetch_add(1, ::std::sync::atomic::Ordering::Relaxed);
+
+        for page_addr in page_range(addr, size) {
+            pages.remove(&page_addr);
+        }
+    }
+
+    pub fn invalidate_all(&self) {
+        let mut pages = self.lock_pages();
+
+        self.epoch
+            .fetch_add(1, ::std::sync::atomic::Ordering::Relaxed);
+
+        pages.clear();
+    }
+
+    #[doc = "Return value: `Number of pages held`, valid or not"]
+    pub fn cached_pages(&self) -> usize {
+        self.lock_pages().len()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(::std::sync::atomic::Ordering::Relaxed),
+            misses: self.misses.load(::std::sync::atomic::Ordering::Relaxed),
+        }
+    }
+
+    pub fn reset_stats(&self) {
+        self.hits.store(0, ::std::sync::atomic::Ordering::Relaxed);
+        self.misses.store(0, ::std::sync::atomic::Ordering::Relaxed);
+    }
+
+    fn lock_pages(&self) -> ::std::sync::MutexGuard<'_, ::std::collections::HashMap<usize, Page>> {
+        self.pages.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn is_valid(&self, page: &Page) -> bool {
+        page.generation == self.generation.load(::std::sync::atomic::Ordering::Relaxed)
+            && self.ttl.is_none_or(|ttl| page.loaded_at.elapsed() < ttl)
+    }
+
+    // Return value: `Was the page cached?`, a stale page is dropped
+    fn read_cached(&self, page_addr: usize, start: usize, dst: &mut [u8]) -> bool {
+        let mut pages = self.lock_pages();
+
+        match pages.get(&page_addr) {
+            Some(page) if self.is_valid(page) => {
+                dst.copy_from_slice(&page.data[start..start + dst.len()]);
+
+                true
+            }
+            Some(_) => {
+                pages.remove(&page_addr);
+
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn insert(&self, page_addr: usize, page: Page, epoch: u64) {
+        let mut pages = self.lock_pages();
+
+        // Written or invalidated while the page was being read
+        if epoch != self.epoch.load(::std::sync::atomic::Ordering::Relaxed) {
+            return;
+        }
+
+        if pages.len() >= self.max_pages && !pages.contains_key(&page_addr) {
+            pages.retain(|_, page| self.is_valid(page));
+
+            if pages.len() >= self.max_pages {
+                let oldest = pages
+                    .iter()
+                    .min_by_key(|(_, page)| page.loaded_at)
+                    .map(|(page_addr, _)| *page_addr);
+
+                if let Some(oldest) = oldest {
+                    pages.remove(&oldest);
+                }
+            }
+        }
+
+        pages.insert(page_addr, page);
+    }
+}
+
+impl<M: MemoryAccess> MemoryAccess for CachedMemory<M> {
+    fn read_bytes(&self, addr: usize, buf: &mut [u8]) -> AnyResult<()> {
+        let mut done = 0;
+
+        for page_addr in page_range(addr, buf.len()) {
+            let start = addr.wrapping_add(done) - page_addr;
+            let len = (PAGE_SIZE - start).min(buf.len() - done);
+
+            let dst = &mut buf[done..done + len];
+
+            done += len;
+
+            if self.read_cached(page_addr, start, dst) {
+                self.hits
+                    .fetch_add(1, ::std::sync::atomic::Ordering::Relaxed);
+
+                continue;
+            }
+
+            self.misses
+                .fetch_add(1, ::std::sync::atomic::Ordering::Relaxed);
+
+            let generation = self.generation.load(::std::sync::atomic::Ordering::Relaxed);
+            let epoch = self.epoch.load(::std::sync::atomic::Ordering::Relaxed);
+
+            let mut data = Box::new([0u8; PAGE_SIZE]);
+
+            match self.inner.read_bytes(page_addr, &mut data[..]) {
+                Ok(()) => {
+                    dst.copy_from_slice(&data[start..start + len]);
+
+                    self.insert(
+                        page_addr,
+                        Page {
+                            data,
+                            loaded_at: ::std::time::Instant::now(),
+                            generation,
+                        },
+                        epoch,
+                    );
+                }
+                // Only part of the page is readable, don't cache it
+                Err(_) => self.inner.read_bytes(page_addr + start, dst)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_bytes(&self, addr: usize, buf: &[u8]) -> AnyResult<()> {
+        let result = self.inner.write_bytes(addr, buf);
+
+        let mut pages = self.lock_pages();
+
+        self.epoch
+            .fetch_add(1, ::std::sync::atomic::Ordering::Relaxed);
+
+        let mut done = 0;
+
+        for page_addr in page_range(addr, buf.len()) {
+            let start = addr.wrapping_add(done) - page_addr;
+            let len = (PAGE_SIZE - start).min(buf.len() - done);
+
+            match result {
+                Ok(()) => {
+                    if let Some(page) = pages.get_mut(&page_addr) {
+                        page.data[start..start + len].copy_from_slice(&buf[done..done + len]);
+                    }
+                }
+                Err(_) => {
+                    pages.remove(&page_addr);
+                }
+            }
+
+            done += len;
+        }
+
+        result
+    }
+
+    fn query(&self, addr: usize) -> AnyResult<crate::types::MemInfo> {
+        self.inner.query(addr)
+    }
+
+    #[inline]
+    fn pointer_width(&self) -> super::PointerWidth {
+        self.inner.pointer_width()
+    }
+}
+
+// Page addresses overlapping `addr..addr + size`
+fn page_range(addr: usize, size: usize) -> impl Iterator<Item = usize> {
+    let first = addr & !(PAGE_SIZE - 1);
+
+    let count = match size {
+        0 => 0,
+        _ => (addr - first + size).div_ceil(PAGE_SIZE),
+    };
+
+    (0..count).map(move |i| first.wrapping_add(i * PAGE_SIZE))
+}
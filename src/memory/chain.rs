@@ -0,0 +1,102 @@
+use super::MemoryAccess;
+
+#[doc = "Pointer size of the target address space"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerWidth {
+    Bits32,
+    Bits64,
+}
+
+impl PointerWidth {
+    #[doc = "Return value: `Pointer width of this build`"]
+    #[inline]
+    pub const fn native() -> Self {
+        if ::core::mem::size_of::<usize>() == 4 {
+            Self::Bits32
+        } else {
+            Self::Bits64
+        }
+    }
+
+    #[doc = "Return value: `Pointer size in bytes`"]
+    #[inline]
+    pub const fn size(self) -> usize {
+        match self {
+            Self::Bits32 => 4,
+            Self::Bits64 => 8,
+        }
+    }
+
+    // Address arithmetic wraps at the target's pointer size
+    #[inline]
+    pub(crate) fn wrap(self, addr: usize) -> usize {
+        match self {
+            Self::Bits32 => addr & u32::MAX as usize,
+            Self::Bits64 => addr,
+        }
+    }
+}
+
+impl Default for PointerWidth {
+    #[inline]
+    fn default() -> Self {
+        Self::native()
+    }
+}
+
+#[doc = r"A pointer chain stopped at `hop`
+
+Hop 0 dereferences the base, hop `n` dereferences the address after adding offset `n - 1`"]
+#[derive(Debug)]
+pub struct PointerChainError {
+    pub hop: usize,
+    #[doc = "Address that could not be read"]
+    pub addr: usize,
+    pub source: Box<dyn ::std::error::Error + Send + Sync>,
+}
+
+impl ::core::fmt::Display for PointerChainError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        write!(
+            f,
+            "pointer chain hop {} failed at {:#X}: {}",
+            self.hop, self.addr, self.source
+        )
+    }
+}
+
+impl ::std::error::Error for PointerChainError {
+    fn source(&self) -> Option<&(dyn ::std::error::Error + 'static)> {
+        Some(&*self.source)
+    }
+}
+
+pub(super) fn read_chain<M: MemoryAccess + ?Sized>(
+    mem: &M,
+    base: usize,
+    byte_offsets: &[isize],
+    width: PointerWidth,
+) -> Result<usize, PointerChainError> {
+    let read = |hop: usize, addr: usize| {
+        mem.read_ptr_width(addr, width)
+            .map_err(|source| PointerChainError { hop, addr, source })
+    };
+
+    let mut ptr = read(0, base)?;
+
+    for (i, byte_offset) in byte_offsets.iter().enumerate() {
+        let addr = width.wrap(ptr.wrapping_add_signed(*byte_offset));
+
+        if 0 == ptr {
+            return Err(PointerChainError {
+                hop: i + 1,
+                addr,
+                source: "null pointer".into(),
+            });
+        }
+
+        ptr = read(i + 1, addr)?;
+    }
+
+    Ok(ptr)
+}
@@ -0,0 +1,316 @@
+use super::{MemoryAccess, Pod};
+use crate::AnyResult;
+
+#[doc = r"Address inside another address space, never dereferenced locally
+
+Every access goes through a `MemoryAccess` backend:
+
+`RemotePtr::<u64>::new(base).byte_offset(0x10).read_ptr::<u32, _>(&mem)?.read(&mem)?`
+
+As a `Pod` field it is `usize` wide, so it only matches the layout of native-width targets.
+Use `RemotePtr32` in structs read from 32-bit targets; `read_ptr` follows `MemoryAccess::pointer_width` either way"]
+#[repr(transparent)]
+pub struct RemotePtr<T> {
+    addr: usize,
+    _marker: ::core::marker::PhantomData<fn() -> T>,
+}
+
+impl<T> RemotePtr<T> {
+    #[inline]
+    pub const fn new(addr: usize) -> Self {
+        Self {
+            addr,
+            _marker: ::core::marker::PhantomData,
+        }
+    }
+
+    #[inline]
+    pub const fn null() -> Self {
+        Self::new(0)
+    }
+
+    #[inline]
+    pub const fn addr(self) -> usize {
+        self.addr
+    }
+
+    #[inline]
+    pub const fn is_null(self) -> bool {
+        self.addr == 0
+    }
+
+    #[doc = "Return value: `Raw pointer`, only meaningful to the process it came from"]
+    #[inline]
+    pub const fn as_ptr(self) -> *const T {
+        self.addr as *const T
+    }
+
+    #[doc = "`count` elements of `T`, like `pointer::wrapping_offset`"]
+    #[inline]
+    pub fn offset(self, count: isize) -> Self {
+        Self::new(
+            self.addr
+                .wrapping_add_signed(count.wrapping_mul(::core::mem::size_of::<T>() as isize)),
+        )
+    }
+
+    #[inline]
+    pub fn byte_offset(self, count: isize) -> Self {
+        Self::new(self.addr.wrapping_add_signed(count))
+    }
+
+    #[inline]
+    pub const fn cast<U>(self) -> RemotePtr<U> {
+        RemotePtr::new(self.addr)
+    }
+
+    pub fn read<M: MemoryAccess>(self, mem: &M) -> AnyResult<T>
+    where
+        T: Pod,
+    {
+        mem.read(self.addr)
+    }
+
+    #[doc = "Return value: `Vec<T>` of `n` consecutive values"]
+    pub fn read_slice<M: MemoryAccess>(self, mem: &M, n: usize) -> AnyResult<Vec<T>>
+    where
+        T: Pod,
+    {
+        mem.read_slice(self.addr, n)
+    }
+
+    pub fn write<M: MemoryAccess>(self, mem: &M, value: &T) -> AnyResult<()>
+    where
+        T: Pod,
+    {
+        mem.write(self.addr, value)
+    }
+
+    #[doc = "Return value: `RemotePtr<U>` stored at the address, `mem.pointer_width()` bytes wide"]
+    pub fn read_ptr<U, M: MemoryAccess>(self, mem: &M) -> AnyResult<RemotePtr<U>> {
+        Ok(RemotePtr::new(mem.read_ptr(self.addr)?))
+    }
+}
+
+unsafe impl<T: 'static> Pod for RemotePtr<T> {}
+
+impl<T> Clone for RemotePtr<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RemotePtr<T> {}
+
+impl<T> Default for RemotePtr<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+impl<T> PartialEq for RemotePtr<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.addr == other.addr
+    }
+}
+
+impl<T> Eq for RemotePtr<T> {}
+
+impl<T> PartialOrd for RemotePtr<T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for RemotePtr<T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+        self.addr.cmp(&other.addr)
+    }
+}
+
+impl<T> ::core::hash::Hash for RemotePtr<T> {
+    fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+        self.addr.hash(state);
+    }
+}
+
+impl<T> ::core::fmt::Debug for RemotePtr<T> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        write!(f, "RemotePtr({:#X})", self.addr)
+    }
+}
+
+impl<T> ::core::fmt::Display for RemotePtr<T> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        write!(f, "{:#X}", self.addr)
+    }
+}
+
+impl<T> From<usize> for RemotePtr<T> {
+    #[inline]
+    fn from(addr: usize) -> Self {
+        Self::new(addr)
+    }
+}
+
+impl<T> From<*const ::core::ffi::c_void> for RemotePtr<T> {
+    #[inline]
+    fn from(ptr: *const ::core::ffi::c_void) -> Self {
+        Self::new(ptr as usize)
+    }
+}
+
+impl<T> From<*mut ::core::ffi::c_void> for RemotePtr<T> {
+    #[inline]
+    fn from(ptr: *mut ::core::ffi::c_void) -> Self {
+        Self::new(ptr as usize)
+    }
+}
+
+impl<T> From<RemotePtr<T>> for usize {
+    #[inline]
+    fn from(ptr: RemotePtr<T>) -> Self {
+        ptr.addr
+    }
+}
+
+#[doc = r"Pointer field of a 32-bit target, 4 bytes wide on any host
+
+`RemotePtr<T>` is `usize` wide, so structs read from a 32-bit process use this instead:
+
+`#[repr(C)] struct Entity { vtable: RemotePtr32<c_void>, health: u32 }`"]
+#[repr(transparent)]
+pub struct RemotePtr32<T> {
+    addr: u32,
+    _marker: ::core::marker::PhantomData<fn() -> T>,
+}
+
+impl<T> RemotePtr32<T> {
+    #[inline]
+    pub const fn new(addr: u32) -> Self {
+        Self {
+            addr,
+            _marker: ::core::marker::PhantomData,
+        }
+    }
+
+    #[inline]
+    pub const fn null() -> Self {
+        Self::new(0)
+    }
+
+    #[inline]
+    pub const fn addr(self) -> u32 {
+        self.addr
+    }
+
+    #[inline]
+    pub const fn is_null(self) -> bool {
+        self.addr == 0
+    }
+
+    #[doc = "Return value: `RemotePtr<T>` with the same address, for offsets and access"]
+    #[inline]
+    pub const fn get(self) -> RemotePtr<T> {
+        RemotePtr::new(self.addr as usize)
+    }
+
+    #[inline]
+    pub const fn cast<U>(self) -> RemotePtr32<U> {
+        RemotePtr32::new(self.addr)
+    }
+
+    pub fn read<M: MemoryAccess>(self, mem: &M) -> AnyResult<T>
+    where
+        T: Pod,
+    {
+        self.get().read(mem)
+    }
+}
+
+unsafe impl<T: 'static> Pod for RemotePtr32<T> {}
+
+impl<T> Clone for RemotePtr32<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RemotePtr32<T> {}
+
+impl<T> Default for RemotePtr32<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+impl<T> PartialEq for RemotePtr32<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.addr == other.addr
+    }
+}
+
+impl<T> Eq for RemotePtr32<T> {}
+
+impl<T> PartialOrd for RemotePtr32<T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for RemotePtr32<T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+        self.addr.cmp(&other.addr)
+    }
+}
+
+impl<T> ::core::hash::Hash for RemotePtr32<T> {
+    fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+        self.addr.hash(state);
+    }
+}
+
+impl<T> ::core::fmt::Debug for RemotePtr32<T> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        write!(f, "RemotePtr32({:#X})", self.addr)
+    }
+}
+
+impl<T> ::core::fmt::Display for RemotePtr32<T> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        write!(f, "{:#X}", self.addr)
+    }
+}
+
+impl<T> From<u32> for RemotePtr32<T> {
+    #[inline]
+    fn from(addr: u32) -> Self {
+        Self::new(addr)
+    }
+}
+
+impl<T> From<RemotePtr32<T>> for RemotePtr<T> {
+    #[inline]
+    fn from(ptr: RemotePtr32<T>) -> Self {
+        ptr.get()
+    }
+}
+
+impl<T> TryFrom<RemotePtr<T>> for RemotePtr32<T> {
+    type Error = ::core::num::TryFromIntError;
+
+    #[inline]
+    fn try_from(ptr: RemotePtr<T>) -> Result<Self, Self::Error> {
+        u32::try_from(ptr.addr).map(Self::new)
+    }
+}
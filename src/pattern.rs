@@ -0,0 +1,509 @@
+use crate::AnyResult;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod simd;
+
+mod ext;
+mod generate;
+mod iter;
+mod set;
+
+pub use self::ext::ExtPattern;
+pub use self::iter::Matches;
+pub use self::set::PatternSet;
+
+// Bytes read at once by `scan_mem`
+const MEM_CHUNK_SIZE: usize = 0x10_0000;
+
+#[cfg(feature = "parallel")]
+const PAR_MIN_CHUNK_SIZE: usize = 0x10_0000;
+
+#[doc = r#"Compiled signature
+
+`pat: "48 8B 05 ?? ?? ?? ?? 00 00"`
+
+Wildcards are kept in a separate mask, so a literal `00` only ever matches `0x00`"#]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    bytes: Vec<u8>,
+    mask: Vec<u8>,
+}
+
+impl Pattern {
+    #[doc = r#"Accepted wildcards: `?`, `??`, `*`, `**`
+
+Half-byte wildcards: `4?` (high nibble `4`), `?8` (low nibble `8`)"#]
+    pub fn parse<S: AsRef<str>>(pat: S) -> AnyResult<Self> {
+        let mut bytes: Vec<u8> = Vec::new();
+        let mut mask: Vec<u8> = Vec::new();
+
+        for pair in pat.as_ref().split_whitespace() {
+            let (byte, byte_mask) = parse_token(pair)?;
+
+            bytes.push(byte);
+            mask.push(byte_mask);
+        }
+
+        if bytes.is_empty() {
+            return Err("\"pat\" is empty".into());
+        }
+
+        Ok(Self { bytes, mask })
+    }
+
+    #[doc = r#"x64dbg style, spaces are optional
+
+`pat: "48 8B 05 ?? ?? ?? ??"` or `pat: "488B05????????"`"#]
+    pub fn parse_x64dbg<S: AsRef<str>>(pat: S) -> AnyResult<Self> {
+        let digits: Vec<char> = pat
+            .as_ref()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+
+        if !digits.len().is_multiple_of(2) {
+            return Err("\"pat\" has an odd number of digits".into());
+        }
+
+        let mut bytes: Vec<u8> = Vec::new();
+        let mut mask: Vec<u8> = Vec::new();
+
+        for pair in digits.chunks(2) {
+            let (byte, byte_mask) = parse_token(&pair.iter().collect::<String>())?;
+
+            bytes.push(byte);
+            mask.push(byte_mask);
+        }
+
+        if bytes.is_empty() {
+            return Err("\"pat\" is empty".into());
+        }
+
+        Ok(Self { bytes, mask })
+    }
+
+    #[doc = r#"Code style, as written in C/C++ sources
+
+`bytes: "\x48\x8B\x05\x00\x00\x00\x00"`, `mask: "xxx????"`"#]
+    pub fn parse_code<B: AsRef<str>, M: AsRef<str>>(bytes: B, mask: M) -> AnyResult<Self> {
+        let mut raw: Vec<u8> = Vec::new();
+
+        for escape in bytes.as_ref().split("\\x").skip(1) {
+            let escape = escape.trim_end_matches(|c: char| c == '"' || c.is_whitespace());
+
+            raw.push(u8::from_str_radix(escape, 16)?);
+        }
+
+        Self::from_code(&raw, mask)
+    }
+
+    #[doc = r#"Code style with the bytes already decoded
+
+`bytes: b"\x48\x8B\x05\x00\x00\x00\x00"`, `mask: "xxx????"`"#]
+    pub fn from_code<M: AsRef<str>>(bytes: &[u8], mask: M) -> AnyResult<Self> {
+        let mask = mask.as_ref();
+
+        if bytes.len() != mask.len() {
+            return Err(format!("{} bytes but {} mask characters", bytes.len(), mask.len()).into());
+        }
+
+        if bytes.is_empty() {
+            return Err("\"pat\" is empty".into());
+        }
+
+        let mask = mask
+            .chars()
+            .map(|c| match c {
+                'x' | 'X' => Ok(0xFF),
+                '?' | '.' => Ok(0x00),
+                _ => Err(format!("invalid mask character: {}", c)),
+            })
+            .collect::<Result<Vec<u8>, String>>()?;
+
+        let bytes = bytes
+            .iter()
+            .zip(mask.iter())
+            .map(|(byte, mask)| byte & mask)
+            .collect();
+
+        Ok(Self { bytes, mask })
+    }
+
+    #[doc = r#"Return value: `"48 8B 05 ? ? ? ?"`"#]
+    pub fn to_ida(&self) -> String {
+        self.to_string().replace("??", "?")
+    }
+
+    #[doc = r#"Return value: `"48 8B 05 ?? ?? ?? ??"`"#]
+    pub fn to_x64dbg(&self) -> String {
+        self.to_string()
+    }
+
+    #[doc = r#"Return value: `("\x48\x8B\x05\x00\x00\x00\x00", "xxx????")`
+
+Fails on half-byte wildcards, the format has no way to express them"#]
+    pub fn to_code(&self) -> AnyResult<(String, String)> {
+        let mut bytes = String::new();
+        let mut mask = String::new();
+
+        for (byte, byte_mask) in self.bytes.iter().zip(self.mask.iter()) {
+            match *byte_mask {
+                0xFF => mask.push('x'),
+                0x00 => mask.push('?'),
+                _ => return Err(format!("\"{}\" has half-byte wildcards", self).into()),
+            }
+
+            bytes.push_str(&format!("\\x{:02X}", byte));
+        }
+
+        Ok((bytes, mask))
+    }
+
+    #[doc = "Return value: `Pattern length in bytes`"]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    #[doc = "Pattern bytes, already masked"]
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    #[doc = "`0xFF` = compare, `0x00` = wildcard, `0xF0`/`0x0F` = compare one nibble"]
+    #[inline]
+    pub fn mask(&self) -> &[u8] {
+        &self.mask
+    }
+
+    #[doc = "Return value: `Does the pattern match data[offset..]?`"]
+    #[inline]
+    pub fn matches_at(&self, data: &[u8], offset: usize) -> bool {
+        match offset
+            .checked_add(self.bytes.len())
+            .and_then(|end| data.get(offset..end))
+        {
+            Some(window) => window
+                .iter()
+                .zip(self.bytes.iter().zip(self.mask.iter()))
+                .all(|(byte, (pat_byte, mask))| byte & mask == *pat_byte),
+            None => false,
+        }
+    }
+
+    #[doc = r"Return value: `Lazy iterator over every match`"]
+    pub fn matches<'d>(&self, data: &'d [u8]) -> Matches<'static, 'd> {
+        Matches::new(::std::borrow::Cow::Owned(self.clone().into()), data)
+    }
+
+    #[doc = r"Return value: `Offset` of the first match
+
+Uses SSE2/AVX2 when the CPU supports it"]
+    pub fn find(&self, data: &[u8]) -> Option<usize> {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if let Some(offset_array) = simd::scan(self, data, true) {
+            return offset_array.first().copied();
+        }
+
+        self.find_scalar(data)
+    }
+
+    #[doc = r"Return value: `Vec<Offset>` of every match
+
+Uses SSE2/AVX2 when the CPU supports it"]
+    pub fn scan(&self, data: &[u8]) -> Vec<usize> {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if let Some(offset_array) = simd::scan(self, data, false) {
+            return offset_array;
+        }
+
+        self.scan_scalar(data)
+    }
+
+    #[doc = r"Return value: `Vec<Address>` of every match in `addr..addr + size`
+
+Memory is read through `mem` in chunks"]
+    pub fn scan_mem<M: crate::memory::MemoryAccess + ?Sized>(
+        &self,
+        mem: &M,
+        addr: usize,
+        size: usize,
+    ) -> AnyResult<Vec<usize>> {
+        scan_mem_chunked(mem, addr, size, self.len(), |chunk| self.scan(chunk))
+    }
+
+    #[doc = r"Return value: `Vec<Offset>` of every match, sorted
+
+Splits `data` into chunks and scans them on every available core; the result is identical to `scan`"]
+    #[cfg(feature = "parallel")]
+    pub fn par_scan(&self, data: &[u8]) -> Vec<usize> {
+        let threads = ::std::thread::available_parallelism().map_or(1, |n| n.get());
+
+        self.par_scan_chunked(data, (data.len() / threads).max(PAR_MIN_CHUNK_SIZE))
+    }
+
+    #[doc = r"Return value: `Vec<Offset>` of every match, sorted
+
+Neighbouring chunks overlap by `pattern length - 1` bytes, so matches straddling a boundary are found"]
+    #[cfg(feature = "parallel")]
+    pub fn par_scan_chunked(&self, data: &[u8], chunk_size: usize) -> Vec<usize> {
+        let chunk_size = chunk_size.max(1);
+
+        let chunk_count = data.len().div_ceil(chunk_size);
+
+        if chunk_count <= 1 {
+            return self.scan(data);
+        }
+
+        let threads = ::std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(chunk_count);
+
+        let next_chunk = ::std::sync::atomic::AtomicUsize::new(0);
+
+        let mut offset_array: Vec<usize> = ::std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut offset_array = Vec::<usize>::new();
+
+                        loop {
+                            let chunk =
+                                next_chunk.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed);
+
+                            if chunk >= chunk_count {
+                                break;
+                            }
+
+                            let start = chunk * chunk_size;
+                            let end = (start + chunk_size + self.len() - 1).min(data.len());
+
+                            offset_array.extend(
+                                self.scan(&data[start..end])
+                                    .into_iter()
+                                    .map(|offset| start + offset),
+                            );
+                        }
+
+                        offset_array
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect()
+        });
+
+        offset_array.sort_unstable();
+        offset_array.dedup();
+
+        offset_array
+    }
+
+    #[doc = r"Return value: `Offset` of the first match
+
+Boyer-Moore-Horspool without vector instructions"]
+    pub fn find_scalar(&self, data: &[u8]) -> Option<usize> {
+        let pat_len = self.bytes.len();
+
+        if pat_len == 0 || data.len() < pat_len {
+            return None;
+        }
+
+        let skip_table = self.skip_table();
+
+        let mut i = 0;
+
+        while i + pat_len <= data.len() {
+            if self.matches_at(data, i) {
+                return Some(i);
+            }
+
+            i += skip_table[data[i + pat_len - 1] as usize];
+        }
+
+        None
+    }
+
+    #[doc = r"Return value: `Vec<Offset>` of every match
+
+Boyer-Moore-Horspool without vector instructions"]
+    pub fn scan_scalar(&self, data: &[u8]) -> Vec<usize> {
+        let pat_len = self.bytes.len();
+
+        let mut offset_array = Vec::<usize>::new();
+
+        if pat_len == 0 || data.len() < pat_len {
+            return offset_array;
+        }
+
+        let skip_table = self.skip_table();
+
+        let mut i = 0;
+
+        while i + pat_len <= data.len() {
+            if self.matches_at(data, i) {
+                offset_array.push(i);
+            }
+
+            i += skip_table[data[i + pat_len - 1] as usize];
+        }
+
+        offset_array
+    }
+
+    // Boyer-Moore-Horspool shift table. A full wildcard can match anything, so
+    // no shift may jump past the last one; a nibble wildcard registers every
+    // byte value it accepts.
+    fn skip_table(&self) -> [usize; 256] {
+        let pat_len = self.bytes.len();
+
+        let last = pat_len - 1;
+
+        let first_literal = self.mask[..last]
+            .iter()
+            .rposition(|mask| *mask == 0)
+            .map_or(0, |i| i + 1);
+
+        let mut skip_table = [pat_len - first_literal; 256];
+
+        for i in first_literal..last {
+            if self.mask[i] == 0xFF {
+                skip_table[self.bytes[i] as usize] = last - i;
+            } else {
+                for byte in 0..=0xFFu8 {
+                    if byte & self.mask[i] == self.bytes[i] {
+                        skip_table[byte as usize] = last - i;
+                    }
+                }
+            }
+        }
+
+        skip_table
+    }
+}
+
+impl ::core::str::FromStr for Pattern {
+    type Err = Box<dyn ::std::error::Error + Send + Sync>;
+
+    fn from_str(pat: &str) -> Result<Self, Self::Err> {
+        Self::parse(pat)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Pattern {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Pattern {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pat = <::std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+
+        Self::parse(pat).map_err(serde::de::Error::custom)
+    }
+}
+
+impl ::core::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        for (i, (byte, mask)) in self.bytes.iter().zip(self.mask.iter()).enumerate() {
+            if i != 0 {
+                f.write_str(" ")?;
+            }
+
+            write_byte(f, *byte, *mask)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_byte(f: &mut ::core::fmt::Formatter<'_>, byte: u8, mask: u8) -> ::core::fmt::Result {
+    match mask {
+        0x00 => f.write_str("??"),
+        0xF0 => write!(f, "{:X}?", byte >> 4),
+        0x0F => write!(f, "?{:X}", byte & 0x0F),
+        _ => write!(f, "{:02X}", byte),
+    }
+}
+
+// Return value: `(Byte, Mask)`
+fn parse_token(pair: &str) -> AnyResult<(u8, u8)> {
+    if pair == "?" || pair == "??" || pair == "*" || pair == "**" {
+        return Ok((0, 0));
+    }
+
+    let mut chars = pair.chars();
+
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some('?'), Some(low), None) => {
+            let num = low.to_digit(16).ok_or(format!("invalid token: {}", pair))? as u8;
+
+            Ok((num, 0x0F))
+        }
+        (Some(high), Some('?'), None) => {
+            let num = high
+                .to_digit(16)
+                .ok_or(format!("invalid token: {}", pair))? as u8;
+
+            Ok((num << 4, 0xF0))
+        }
+        // `from_str_radix` alone would accept a leading `+`
+        _ if !pair.is_empty() && pair.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Ok((u8::from_str_radix(pair, 16)?, 0xFF))
+        }
+        _ => Err(format!("invalid token: {}", pair).into()),
+    }
+}
+
+// Chunks overlap by `max_len - 1` bytes, a match belongs to the chunk it starts in
+fn scan_mem_chunked<M, F>(
+    mem: &M,
+    addr: usize,
+    size: usize,
+    max_len: usize,
+    mut scan: F,
+) -> AnyResult<Vec<usize>>
+where
+    M: crate::memory::MemoryAccess + ?Sized,
+    F: FnMut(&[u8]) -> Vec<usize>,
+{
+    let mut addr_array: Vec<usize> = Vec::new();
+
+    let mut buf: Vec<u8> = Vec::new();
+
+    let mut start = 0;
+
+    while start < size {
+        let end = start
+            .saturating_add(MEM_CHUNK_SIZE)
+            .saturating_add(max_len.saturating_sub(1))
+            .min(size);
+
+        buf.resize(end - start, 0);
+
+        mem.read_bytes(addr.wrapping_add(start), &mut buf)?;
+
+        addr_array.extend(
+            scan(&buf)
+                .into_iter()
+                .filter(|offset| *offset < MEM_CHUNK_SIZE)
+                .map(|offset| addr.wrapping_add(start + offset)),
+        );
+
+        start += MEM_CHUNK_SIZE;
+    }
+
+    Ok(addr_array)
+}
@@ -0,0 +1,386 @@
+use super::{parse_token, write_byte, Pattern};
+use crate::AnyResult;
+
+// Longest accepted `[min-max]` gap
+const MAX_GAP: usize = 0x1000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Elem {
+    Byte { value: u8, mask: u8 },
+    Not { value: u8, mask: u8 },
+    // 256 bit set of accepted bytes
+    Set([u64; 4]),
+    Gap { min: usize, max: usize },
+}
+
+impl Elem {
+    #[inline]
+    fn accepts(&self, byte: u8) -> bool {
+        match *self {
+            Elem::Byte { value, mask } => byte & mask == value,
+            Elem::Not { value, mask } => byte & mask != value,
+            Elem::Set(ref set) => set[byte as usize >> 6] & (1 << (byte & 63)) != 0,
+            Elem::Gap { .. } => true,
+        }
+    }
+}
+
+#[doc = r#"Signature with variable-length gaps, byte sets and negation
+
+| Token | Meaning |
+|---|---|
+| `48`, `4?`, `??` | Same as `Pattern` |
+| `[4-16]`, `[8]` | Skip 4 to 16 (exactly 8) bytes, decimal |
+| `[70-7F]` | Any byte in the hex range, e.g. any short `Jcc` |
+| `(74\|75)`, `(70-7F\|E9)` | Any of the listed bytes or hex ranges |
+| `!00` | Any byte except `00` |
+
+A bracket with both bounds exactly two hex digits is a byte range, anything else in brackets is a gap.
+Gaps with two-digit bounds are written with a leading zero: `[010-020]` skips 10 to 20 bytes, `[10-20]` is the range `10`-`20`.
+
+Patterns using none of the extended tokens are scanned by `Pattern`"#]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExtPattern {
+    elems: Vec<Elem>,
+    plain: Option<Pattern>,
+}
+
+impl ExtPattern {
+    pub fn parse<S: AsRef<str>>(pat: S) -> AnyResult<Self> {
+        let mut elems: Vec<Elem> = Vec::new();
+
+        for token in pat.as_ref().split_whitespace() {
+            elems.push(parse_elem(token)?);
+        }
+
+        if elems.is_empty() {
+            return Err("\"pat\" is empty".into());
+        }
+
+        if elems.iter().all(|elem| matches!(elem, Elem::Gap { .. })) {
+            return Err("\"pat\" has nothing but gaps".into());
+        }
+
+        let plain = elems
+            .iter()
+            .map(|elem| match *elem {
+                Elem::Byte { value, mask } => Some((value, mask)),
+                _ => None,
+            })
+            .collect::<Option<Vec<(u8, u8)>>>()
+            .map(|pairs| Pattern {
+                bytes: pairs.iter().map(|(value, _)| *value).collect(),
+                mask: pairs.iter().map(|(_, mask)| *mask).collect(),
+            });
+
+        Ok(Self { elems, plain })
+    }
+
+    #[doc = "Return value: `Pattern`, if no extended token is used"]
+    #[inline]
+    pub fn as_plain(&self) -> Option<&Pattern> {
+        self.plain.as_ref()
+    }
+
+    #[doc = "Return value: `(Shortest, Longest)` match length in bytes"]
+    pub fn len_range(&self) -> (usize, usize) {
+        self.elems
+            .iter()
+            .fold((0, 0), |(min, max), elem| match *elem {
+                Elem::Gap {
+                    min: gap_min,
+                    max: gap_max,
+                } => (min + gap_min, max + gap_max),
+                _ => (min + 1, max + 1),
+            })
+    }
+
+    #[doc = "Return value: `Does the pattern match data[offset..]?`"]
+    pub fn matches_at(&self, data: &[u8], offset: usize) -> bool {
+        match self.plain {
+            Some(ref plain) => plain.matches_at(data, offset),
+            None => match_elems(&self.elems, data, offset).is_some(),
+        }
+    }
+
+    #[doc = r"Return value: `Offset` of the first match"]
+    pub fn find(&self, data: &[u8]) -> Option<usize> {
+        match self.plain {
+            Some(ref plain) => plain.find(data),
+            None => self
+                .candidates(data)
+                .find(|i| match_elems(&self.elems, data, *i).is_some()),
+        }
+    }
+
+    #[doc = r"Return value: `Vec<Offset>` of every match start"]
+    pub fn scan(&self, data: &[u8]) -> Vec<usize> {
+        match self.plain {
+            Some(ref plain) => plain.scan(data),
+            None => self
+                .candidates(data)
+                .filter(|i| match_elems(&self.elems, data, *i).is_some())
+                .collect(),
+        }
+    }
+
+    #[doc = r"Return value: `Vec<Address>` of every match start in `addr..addr + size`
+
+Memory is read through `mem` in chunks"]
+    pub fn scan_mem<M: crate::memory::MemoryAccess + ?Sized>(
+        &self,
+        mem: &M,
+        addr: usize,
+        size: usize,
+    ) -> AnyResult<Vec<usize>> {
+        super::scan_mem_chunked(mem, addr, size, self.len_range().1, |chunk| {
+            self.scan(chunk)
+        })
+    }
+
+    #[doc = r"Return value: `Lazy iterator over every match`"]
+    pub fn matches<'d>(&self, data: &'d [u8]) -> super::Matches<'_, 'd> {
+        super::Matches::new(::std::borrow::Cow::Borrowed(self), data)
+    }
+
+    // Return value: `End` of the match at `offset`
+    pub(super) fn match_end(&self, data: &[u8], offset: usize) -> Option<usize> {
+        match self.plain {
+            Some(ref plain) => plain.matches_at(data, offset).then(|| offset + plain.len()),
+            None => match_elems(&self.elems, data, offset),
+        }
+    }
+
+    // Start offsets that leave room for the shortest match and pass the first element
+    fn candidates<'a>(&'a self, data: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        let end = (data.len() + 1).saturating_sub(self.len_range().0);
+
+        (0..end).filter(move |i| match self.elems[0] {
+            Elem::Gap { .. } => true,
+            ref elem => elem.accepts(data[*i]),
+        })
+    }
+}
+
+impl From<Pattern> for ExtPattern {
+    fn from(pat: Pattern) -> Self {
+        Self {
+            elems: pat
+                .bytes
+                .iter()
+                .zip(pat.mask.iter())
+                .map(|(value, mask)| Elem::Byte {
+                    value: *value,
+                    mask: *mask,
+                })
+                .collect(),
+            plain: Some(pat),
+        }
+    }
+}
+
+impl ::core::str::FromStr for ExtPattern {
+    type Err = Box<dyn ::std::error::Error + Send + Sync>;
+
+    fn from_str(pat: &str) -> Result<Self, Self::Err> {
+        Self::parse(pat)
+    }
+}
+
+impl ::core::fmt::Display for ExtPattern {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        for (i, elem) in self.elems.iter().enumerate() {
+            if i != 0 {
+                f.write_str(" ")?;
+            }
+
+            match *elem {
+                Elem::Byte { value, mask } => write_byte(f, value, mask)?,
+                Elem::Not { value, mask } => {
+                    f.write_str("!")?;
+                    write_byte(f, value, mask)?;
+                }
+                Elem::Set(ref set) => {
+                    f.write_str("(")?;
+
+                    let mut first = true;
+                    let mut byte = 0usize;
+
+                    while byte < 256 {
+                        if !elem.accepts(byte as u8) {
+                            byte += 1;
+                            continue;
+                        }
+
+                        let start = byte;
+
+                        while byte + 1 < 256 && set[(byte + 1) >> 6] & (1 << ((byte + 1) & 63)) != 0
+                        {
+                            byte += 1;
+                        }
+
+                        if !first {
+                            f.write_str("|")?;
+                        }
+
+                        first = false;
+
+                        if start == byte {
+                            write!(f, "{:02X}", start)?;
+                        } else {
+                            write!(f, "{:02X}-{:02X}", start, byte)?;
+                        }
+
+                        byte += 1;
+                    }
+
+                    f.write_str(")")?;
+                }
+                Elem::Gap { min, max } if min == max => write!(f, "[{}]", min)?,
+                // `[10-20]` would read back as a byte range
+                Elem::Gap { min, max } if min >= 10 && max < 100 => {
+                    write!(f, "[{:03}-{:03}]", min, max)?
+                }
+                Elem::Gap { min, max } => write!(f, "[{}-{}]", min, max)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Return value: `End` of the shortest match starting at `pos`
+//
+// Keeps the sorted set of positions reachable after each element instead of backtracking,
+// so a gap costs at most `data.len()` positions no matter how many gaps came before
+fn match_elems(elems: &[Elem], data: &[u8], pos: usize) -> Option<usize> {
+    let mut live: Vec<usize> = vec![pos];
+    let mut next: Vec<usize> = Vec::new();
+
+    for elem in elems {
+        next.clear();
+
+        match *elem {
+            Elem::Gap { min, max } => {
+                // Shifted ranges of a sorted set overlap, skip what is already in `next`
+                let mut covered = 0;
+
+                for p in &live {
+                    let start = p.saturating_add(min).max(covered);
+                    let end = p.saturating_add(max).min(data.len()) + 1;
+
+                    next.extend(start..end);
+
+                    covered = covered.max(end);
+                }
+            }
+            _ => next.extend(
+                live.iter()
+                    .filter(|p| data.get(**p).is_some_and(|byte| elem.accepts(*byte)))
+                    .map(|p| p + 1),
+            ),
+        }
+
+        if next.is_empty() {
+            return None;
+        }
+
+        ::core::mem::swap(&mut live, &mut next);
+    }
+
+    live.first().copied()
+}
+
+fn parse_elem(token: &str) -> AnyResult<Elem> {
+    if let Some(rest) = token.strip_prefix('!') {
+        let (value, mask) = parse_token(rest)?;
+
+        if mask == 0 {
+            return Err(format!("\"{}\" never matches", token).into());
+        }
+
+        return Ok(Elem::Not { value, mask });
+    }
+
+    if let Some(inner) = token.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        let mut set = [0u64; 4];
+
+        for alt in inner.split('|') {
+            match alt.split_once('-') {
+                Some((low, high)) => insert_range(&mut set, parse_byte(low)?, parse_byte(high)?)?,
+                None => {
+                    let (value, mask) = parse_token(alt)?;
+
+                    for byte in 0..=0xFFu8 {
+                        if byte & mask == value {
+                            set[byte as usize >> 6] |= 1 << (byte & 63);
+                        }
+                    }
+                }
+            }
+        }
+
+        return Ok(Elem::Set(set));
+    }
+
+    if let Some(inner) = token.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        if let Some((low, high)) = inner
+            .split_once('-')
+            .filter(|(low, high)| is_range_bound(low) && is_range_bound(high))
+        {
+            let mut set = [0u64; 4];
+
+            insert_range(&mut set, parse_byte(low)?, parse_byte(high)?)?;
+
+            return Ok(Elem::Set(set));
+        }
+
+        let (low, high) = inner.split_once('-').unwrap_or((inner, inner));
+
+        let min = parse_gap_len(low).ok_or_else(|| format!("invalid gap: {}", token))?;
+        let max = parse_gap_len(high).ok_or_else(|| format!("invalid gap: {}", token))?;
+
+        if min > max || max > MAX_GAP {
+            return Err(format!("invalid gap: {}", token).into());
+        }
+
+        return Ok(Elem::Gap { min, max });
+    }
+
+    let (value, mask) = parse_token(token)?;
+
+    Ok(Elem::Byte { value, mask })
+}
+
+fn parse_byte(s: &str) -> AnyResult<u8> {
+    match parse_token(s)? {
+        (value, 0xFF) => Ok(value),
+        _ => Err(format!("wildcard in range: {}", s).into()),
+    }
+}
+
+// `[HH-HH]` is a byte range rather than a gap
+fn is_range_bound(s: &str) -> bool {
+    2 == s.len() && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// Decimal, digits only
+fn parse_gap_len(s: &str) -> Option<usize> {
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+fn insert_range(set: &mut [u64; 4], low: u8, high: u8) -> AnyResult<()> {
+    if low > high {
+        return Err(format!("invalid range: {:02X}-{:02X}", low, high).into());
+    }
+
+    for byte in low..=high {
+        set[byte as usize >> 6] |= 1 << (byte & 63);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use super::Pattern;
+use crate::AnyResult;
+
+// Signatures longer than this are not worth keeping
+const MAX_SIG_LEN: usize = 128;
+
+impl Pattern {
+    #[doc = r#"Return value: `Shortest pattern that matches only data[offset..]`
+
+Instructions are decoded from `offset` on; branch targets, RIP-relative and absolute addresses, and 32/64-bit immediates are wildcarded"#]
+    pub fn generate(data: &[u8], offset: usize) -> AnyResult<Self> {
+        if offset >= data.len() {
+            return Err(format!("{:#X} is outside of the buffer", offset).into());
+        }
+
+        let mut pat = Self {
+            bytes: Vec::new(),
+            mask: Vec::new(),
+        };
+
+        let mut pos = offset;
+
+        while pat.len() < MAX_SIG_LEN {
+            let insn = crate::x86::decode(&data[pos..])
+                .ok_or_else(|| format!("can't decode the instruction at {:#X}", pos))?;
+
+            let insn_start = pat.len();
+
+            pat.bytes.extend_from_slice(&data[pos..pos + insn.len]);
+            pat.mask.resize(pat.bytes.len(), 0xFF);
+
+            for operand in volatile_operands(&insn) {
+                pat.mask[insn_start + operand.offset..][..operand.size].fill(0);
+                pat.bytes[insn_start + operand.offset..][..operand.size].fill(0);
+            }
+
+            pos += insn.len;
+
+            // Extending a pattern only ever drops matches, so the shortest unique
+            // prefix lies inside the first instruction that makes it unique
+            if is_unique(&pat, data, offset) {
+                for len in insn_start + 1..pat.len() {
+                    if pat.mask[len - 1] == 0 {
+                        continue;
+                    }
+
+                    let prefix = Self {
+                        bytes: pat.bytes[..len].to_vec(),
+                        mask: pat.mask[..len].to_vec(),
+                    };
+
+                    if is_unique(&prefix, data, offset) {
+                        return Ok(prefix);
+                    }
+                }
+
+                return Ok(pat);
+            }
+
+            if pos >= data.len() {
+                break;
+            }
+        }
+
+        Err(format!("no unique signature for {:#X}", offset).into())
+    }
+}
+
+fn volatile_operands(insn: &crate::x86::Insn) -> Vec<crate::x86::Operand> {
+    let mut operands = Vec::new();
+
+    if let Some(rel) = insn.rel {
+        if rel.size == 4 {
+            operands.push(rel);
+        }
+    }
+
+    if let Some(disp) = insn.disp {
+        if insn.rip_relative || insn.absolute {
+            operands.push(disp);
+        }
+    }
+
+    if let Some(imm) = insn.imm {
+        if imm.size >= 4 {
+            operands.push(imm);
+        }
+    }
+
+    operands
+}
+
+fn is_unique(pat: &Pattern, data: &[u8], offset: usize) -> bool {
+    if pat.mask.iter().all(|mask| *mask == 0) {
+        return false;
+    }
+
+    match pat.find(data) {
+        Some(first) if first == offset => pat.find(&data[offset + 1..]).is_none(),
+        _ => false,
+    }
+}
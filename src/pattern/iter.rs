@@ -0,0 +1,70 @@
+use super::ExtPattern;
+
+#[doc = r#"Lazy iterator over the matches of a pattern
+
+Matches are returned in order and may overlap unless `overlapping(false)` is set. `start`/`end` restrict the search to `data[start..end]`; offsets stay relative to `data`"#]
+#[derive(Debug, Clone)]
+pub struct Matches<'p, 'd> {
+    pat: ::std::borrow::Cow<'p, ExtPattern>,
+    data: &'d [u8],
+    pos: usize,
+    end: usize,
+    overlapping: bool,
+}
+
+impl<'p, 'd> Matches<'p, 'd> {
+    pub(crate) fn new(pat: ::std::borrow::Cow<'p, ExtPattern>, data: &'d [u8]) -> Self {
+        Self {
+            pat,
+            data,
+            pos: 0,
+            end: data.len(),
+            overlapping: true,
+        }
+    }
+
+    #[doc = "First offset a match may start at"]
+    pub fn start(mut self, offset: usize) -> Self {
+        self.pos = offset;
+
+        self
+    }
+
+    #[doc = "Matches must end at or before this offset"]
+    pub fn end(mut self, offset: usize) -> Self {
+        self.end = offset.min(self.data.len());
+
+        self
+    }
+
+    #[doc = "The next search starts after the end of the previous match when `false`"]
+    pub fn overlapping(mut self, overlapping: bool) -> Self {
+        self.overlapping = overlapping;
+
+        self
+    }
+}
+
+impl Iterator for Matches<'_, '_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let window = self.data.get(self.pos..self.end)?;
+
+        let offset = self.pos + self.pat.find(window)?;
+
+        // Bounded by `end`, like the window it was found in
+        let match_end = self
+            .pat
+            .match_end(&self.data[..self.end], offset)
+            .unwrap_or(offset + 1);
+
+        self.pos = if self.overlapping {
+            offset + 1
+        } else {
+            match_end.max(offset + 1)
+        };
+
+        Some(offset)
+    }
+}
@@ -0,0 +1,187 @@
+use super::Pattern;
+
+#[doc = r#"Named signatures scanned together in a single pass
+
+Each signature is anchored on its longest literal run; the runs are searched with an Aho-Corasick automaton and every hit is verified against the full pattern"#]
+#[derive(Debug, Clone, Default)]
+pub struct PatternSet {
+    entries: Vec<(String, Pattern)>,
+}
+
+impl PatternSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[doc = "An entry with the same name is replaced"]
+    pub fn add<S: Into<String>>(&mut self, name: S, pat: Pattern) -> &mut Self {
+        let name = name.into();
+
+        match self.entries.iter_mut().find(|(n, _)| *n == name) {
+            Some(entry) => entry.1 = pat,
+            None => self.entries.push((name, pat)),
+        }
+
+        self
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Pattern)> {
+        self.entries.iter().map(|(name, pat)| (name.as_str(), pat))
+    }
+
+    #[doc = r"Return value: `HashMap<Name, Vec<Offset>>`
+
+Every name is present, signatures without a match map to an empty `Vec`"]
+    pub fn scan(&self, data: &[u8]) -> ::std::collections::HashMap<String, Vec<usize>> {
+        let mut offset_arrays: Vec<Vec<usize>> = vec![Vec::new(); self.entries.len()];
+
+        let mut anchors: Vec<(usize, usize, usize)> = Vec::new();
+
+        for (index, (_, pat)) in self.entries.iter().enumerate() {
+            match longest_literal_run(pat) {
+                Some((start, len)) => anchors.push((index, start, len)),
+                // Nothing to anchor on, fall back to a dedicated scan
+                None => offset_arrays[index] = pat.scan(data),
+            }
+        }
+
+        if !anchors.is_empty() {
+            let automaton =
+                Automaton::new(anchors.iter().map(|(index, start, len)| {
+                    &self.entries[*index].1.bytes[*start..*start + len]
+                }));
+
+            let mut state = 0;
+
+            for (i, byte) in data.iter().enumerate() {
+                state = automaton.table[state][*byte as usize] as usize;
+
+                for anchor in &automaton.out[state] {
+                    let (index, start, len) = anchors[*anchor as usize];
+
+                    let Some(offset) = (i + 1).checked_sub(start + len) else {
+                        continue;
+                    };
+
+                    if self.entries[index].1.matches_at(data, offset) {
+                        offset_arrays[index].push(offset);
+                    }
+                }
+            }
+        }
+
+        self.entries
+            .iter()
+            .zip(offset_arrays)
+            .map(|((name, _), offset_array)| (name.clone(), offset_array))
+            .collect()
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, Pattern)> for PatternSet {
+    fn from_iter<I: IntoIterator<Item = (S, Pattern)>>(iter: I) -> Self {
+        let mut set = Self::new();
+
+        for (name, pat) in iter {
+            set.add(name, pat);
+        }
+
+        set
+    }
+}
+
+// Return value: `(Start, Len)`
+fn longest_literal_run(pat: &Pattern) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize)> = None;
+
+    let mut run_start = 0;
+
+    for i in 0..=pat.len() {
+        if i < pat.len() && pat.mask[i] == 0xFF {
+            continue;
+        }
+
+        let len = i - run_start;
+
+        if len > 0 && best.is_none_or(|(_, best_len)| len > best_len) {
+            best = Some((run_start, len));
+        }
+
+        run_start = i + 1;
+    }
+
+    best
+}
+
+// Dense Aho-Corasick DFA, state 0 is the root
+struct Automaton {
+    table: Vec<[u32; 256]>,
+    out: Vec<Vec<u32>>,
+}
+
+impl Automaton {
+    fn new<'a, I: Iterator<Item = &'a [u8]>>(keys: I) -> Self {
+        let mut table: Vec<[u32; 256]> = vec![[0; 256]];
+        let mut out: Vec<Vec<u32>> = vec![Vec::new()];
+
+        for (id, key) in keys.enumerate() {
+            let mut state = 0;
+
+            for byte in key {
+                if table[state][*byte as usize] == 0 {
+                    table.push([0; 256]);
+                    out.push(Vec::new());
+
+                    table[state][*byte as usize] = (table.len() - 1) as u32;
+                }
+
+                state = table[state][*byte as usize] as usize;
+            }
+
+            out[state].push(id as u32);
+        }
+
+        // Breadth first, so a failure state is always complete before it is used
+        let mut fail: Vec<u32> = vec![0; table.len()];
+        let mut queue = ::std::collections::VecDeque::<usize>::new();
+
+        queue.extend(
+            table[0]
+                .iter()
+                .filter(|child| **child != 0)
+                .map(|child| *child as usize),
+        );
+
+        while let Some(state) = queue.pop_front() {
+            let fail_row = table[fail[state] as usize];
+
+            for (byte, fallback) in fail_row.into_iter().enumerate() {
+                let child = table[state][byte] as usize;
+
+                if child == 0 {
+                    table[state][byte] = fallback;
+                    continue;
+                }
+
+                fail[child] = fallback;
+
+                let inherited = out[fallback as usize].clone();
+                out[child].extend(inherited);
+
+                queue.push_back(child);
+            }
+        }
+
+        Self { table, out }
+    }
+}
@@ -0,0 +1,147 @@
+#[cfg(target_arch = "x86")]
+use ::core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use ::core::arch::x86_64::*;
+
+use super::Pattern;
+
+// Return value: `None` when no vector backend applies and the scalar path has to run
+pub(super) fn scan(pat: &Pattern, data: &[u8], first_only: bool) -> Option<Vec<usize>> {
+    let (first, second) = anchors(pat)?;
+
+    if ::std::is_x86_feature_detected!("avx2") {
+        return Some(unsafe { scan_avx2(pat, data, first, second, first_only) });
+    }
+
+    if ::std::is_x86_feature_detected!("sse2") {
+        return Some(unsafe { scan_sse2(pat, data, first, second, first_only) });
+    }
+
+    None
+}
+
+// The first and the last fully literal byte; both are compared per block so a
+// common first byte such as `48` doesn't flood the verifier with candidates.
+fn anchors(pat: &Pattern) -> Option<(usize, usize)> {
+    let first = pat.mask.iter().position(|mask| *mask == 0xFF)?;
+    let second = pat.mask.iter().rposition(|mask| *mask == 0xFF)?;
+
+    Some((first, second))
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn scan_avx2(
+    pat: &Pattern,
+    data: &[u8],
+    first: usize,
+    second: usize,
+    first_only: bool,
+) -> Vec<usize> {
+    let mut offset_array = Vec::<usize>::new();
+
+    let first_byte = _mm256_set1_epi8(pat.bytes[first] as i8);
+    let second_byte = _mm256_set1_epi8(pat.bytes[second] as i8);
+
+    let mut i = 0;
+
+    while i + second + 32 <= data.len() {
+        let first_block = _mm256_loadu_si256(data.as_ptr().add(i + first).cast());
+        let second_block = _mm256_loadu_si256(data.as_ptr().add(i + second).cast());
+
+        let eq = _mm256_and_si256(
+            _mm256_cmpeq_epi8(first_block, first_byte),
+            _mm256_cmpeq_epi8(second_block, second_byte),
+        );
+
+        let mut bits = _mm256_movemask_epi8(eq) as u32;
+
+        while bits != 0 {
+            let offset = i + bits.trailing_zeros() as usize;
+
+            if pat.matches_at(data, offset) {
+                offset_array.push(offset);
+
+                if first_only {
+                    return offset_array;
+                }
+            }
+
+            bits &= bits - 1;
+        }
+
+        i += 32;
+    }
+
+    scan_tail(pat, data, i, first_only, &mut offset_array);
+
+    offset_array
+}
+
+#[target_feature(enable = "sse2")]
+unsafe fn scan_sse2(
+    pat: &Pattern,
+    data: &[u8],
+    first: usize,
+    second: usize,
+    first_only: bool,
+) -> Vec<usize> {
+    let mut offset_array = Vec::<usize>::new();
+
+    let first_byte = _mm_set1_epi8(pat.bytes[first] as i8);
+    let second_byte = _mm_set1_epi8(pat.bytes[second] as i8);
+
+    let mut i = 0;
+
+    while i + second + 16 <= data.len() {
+        let first_block = _mm_loadu_si128(data.as_ptr().add(i + first).cast());
+        let second_block = _mm_loadu_si128(data.as_ptr().add(i + second).cast());
+
+        let eq = _mm_and_si128(
+            _mm_cmpeq_epi8(first_block, first_byte),
+            _mm_cmpeq_epi8(second_block, second_byte),
+        );
+
+        let mut bits = _mm_movemask_epi8(eq) as u32;
+
+        while bits != 0 {
+            let offset = i + bits.trailing_zeros() as usize;
+
+            if pat.matches_at(data, offset) {
+                offset_array.push(offset);
+
+                if first_only {
+                    return offset_array;
+                }
+            }
+
+            bits &= bits - 1;
+        }
+
+        i += 16;
+    }
+
+    scan_tail(pat, data, i, first_only, &mut offset_array);
+
+    offset_array
+}
+
+// Fewer bytes than one block remain, check them one by one
+fn scan_tail(
+    pat: &Pattern,
+    data: &[u8],
+    mut i: usize,
+    first_only: bool,
+    offset_array: &mut Vec<usize>,
+) {
+    while i + pat.len() <= data.len() {
+        if pat.matches_at(data, i) {
+            offset_array.push(i);
+
+            if first_only {
+                return;
+            }
+        }
+
+        i += 1;
+    }
+}
@@ -0,0 +1,254 @@
+use crate::AnyResult;
+#[cfg(any(windows, target_os = "linux"))]
+use crate::HANDLE;
+
+#[doc = r#"Pointer path as shared between tools: `"game.exe"+1A2B3C -> 10 -> 28 +8`
+
+| Part | Meaning |
+|---|---|
+| `"game.exe"+1A2B3C` | Base slot, relative to the module; `"game.exe"` alone is offset 0 |
+| `7FF61A2B3C` | Absolute base slot, no module |
+| `-> 10`, `-> -10` | Add the offset to the pointer read so far, then dereference |
+| `+8`, `-8` | Final offset, added without dereferencing |
+
+Numbers are hex with an optional `0x`. Unquoted module names work when they aren't plain hex,
+quote names containing spaces or `-`.
+
+The base slot is always dereferenced, so the path resolves like `read_multi_pointer(base, offsets) + last_offset`"#]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PointerPath {
+    #[doc = "`None` if `base` is an absolute address"]
+    pub module: Option<String>,
+    pub base: usize,
+    pub offsets: Vec<isize>,
+    #[doc = "Added after the last dereference"]
+    pub last_offset: isize,
+}
+
+impl PointerPath {
+    pub fn parse<S: AsRef<str>>(path: S) -> AnyResult<Self> {
+        let mut cursor = Cursor {
+            rest: path.as_ref().trim_start(),
+        };
+
+        if cursor.rest.is_empty() {
+            return Err("\"path\" is empty".into());
+        }
+
+        let mut pointer_path = Self::default();
+
+        if let Some(quoted) = cursor.rest.strip_prefix('"') {
+            let end = quoted.find('"').ok_or("unterminated module name")?;
+
+            pointer_path.module = Some(quoted[..end].to_owned());
+
+            cursor.rest = &quoted[end + 1..];
+
+            if cursor.eat("+") {
+                pointer_path.base = cursor.hex()?;
+            }
+        } else {
+            let len = cursor
+                .rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+                .unwrap_or(cursor.rest.len());
+
+            let name = &cursor.rest[..len];
+
+            match parse_hex(name) {
+                Some(base) => {
+                    pointer_path.base = base;
+
+                    cursor.rest = &cursor.rest[len..];
+                }
+                None if name.is_empty() => {
+                    return Err(
+                        format!("expected a module or an address at \"{}\"", cursor.rest).into(),
+                    )
+                }
+                None => {
+                    pointer_path.module = Some(name.to_owned());
+
+                    cursor.rest = &cursor.rest[len..];
+
+                    if cursor.eat("+") {
+                        pointer_path.base = cursor.hex()?;
+                    }
+                }
+            }
+        }
+
+        while cursor.eat("->") {
+            pointer_path.offsets.push(cursor.signed_hex(false)?);
+        }
+
+        if !cursor.is_empty() {
+            pointer_path.last_offset = cursor.signed_hex(true)?;
+        }
+
+        if !cursor.is_empty() {
+            return Err(format!("unexpected \"{}\"", cursor.rest.trim()).into());
+        }
+
+        Ok(pointer_path)
+    }
+
+    #[doc = r"Return value: `Final address`
+
+`module_base` is the address of `module` and is ignored for an absolute base"]
+    pub fn resolve_mem<M: crate::memory::MemoryAccess + ?Sized>(
+        &self,
+        mem: &M,
+        module_base: usize,
+    ) -> AnyResult<usize> {
+        let base = match self.module {
+            Some(_) => module_base.wrapping_add(self.base),
+            None => self.base,
+        };
+
+        Ok(mem
+            .read_multi_pointer(base, &self.offsets)?
+            .wrapping_add_signed(self.last_offset))
+    }
+
+    #[doc = r"Return value: `Final address`
+
+The module is looked up with `get_mod_info`"]
+    #[cfg(any(windows, target_os = "linux"))]
+    pub unsafe fn resolve_remote(
+        &self,
+        proc_handle: HANDLE,
+        pid: u32,
+    ) -> AnyResult<*const ::core::ffi::c_void> {
+        let module_base = match self.module {
+            Some(ref module) => crate::external::get_mod_info(pid, module)?.addr as usize,
+            None => 0,
+        };
+
+        let addr =
+            self.resolve_mem(&crate::memory::RemoteMemory::new(proc_handle)?, module_base)?;
+
+        Ok(addr as *const ::core::ffi::c_void)
+    }
+}
+
+impl ::core::str::FromStr for PointerPath {
+    type Err = Box<dyn ::std::error::Error + Send + Sync>;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Self::parse(path)
+    }
+}
+
+impl ::core::fmt::Display for PointerPath {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        match self.module {
+            Some(ref module) if 0 == self.base => write!(f, "\"{}\"", module)?,
+            Some(ref module) => write!(f, "\"{}\"+{:X}", module, self.base)?,
+            None => write!(f, "{:X}", self.base)?,
+        }
+
+        for offset in &self.offsets {
+            match *offset < 0 {
+                true => write!(f, " -> -{:X}", offset.unsigned_abs())?,
+                false => write!(f, " -> {:X}", offset)?,
+            }
+        }
+
+        match self.last_offset {
+            0 => Ok(()),
+            n if n < 0 => write!(f, " -{:X}", n.unsigned_abs()),
+            n => write!(f, " +{:X}", n),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PointerPath {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PointerPath {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let path = <::std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+
+        Self::parse(path).map_err(serde::de::Error::custom)
+    }
+}
+
+struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl Cursor<'_> {
+    fn is_empty(&self) -> bool {
+        self.rest.trim_start().is_empty()
+    }
+
+    // Skips whitespace, then `token` if it is next
+    fn eat(&mut self, token: &str) -> bool {
+        self.rest = self.rest.trim_start();
+
+        match self.rest.strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn hex(&mut self) -> AnyResult<usize> {
+        self.rest = self.rest.trim_start();
+
+        let len = self
+            .rest
+            .strip_prefix("0x")
+            .or_else(|| self.rest.strip_prefix("0X"))
+            .map_or(0, |_| 2);
+
+        let end = self.rest[len..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .map_or(self.rest.len(), |end| len + end);
+
+        let number = parse_hex(&self.rest[..end])
+            .ok_or_else(|| format!("expected a hex number at \"{}\"", self.rest))?;
+
+        self.rest = &self.rest[end..];
+
+        Ok(number)
+    }
+
+    fn signed_hex(&mut self, sign_required: bool) -> AnyResult<isize> {
+        self.rest = self.rest.trim_start();
+
+        let negative = self.eat("-");
+
+        if !negative && !self.eat("+") && sign_required {
+            return Err(format!("expected \"+\" or \"-\" at \"{}\"", self.rest).into());
+        }
+
+        let magnitude = self.hex()? as i128;
+
+        let value = if negative { -magnitude } else { magnitude };
+
+        isize::try_from(value).map_err(|_| format!("offset {:X} is too large", magnitude).into())
+    }
+}
+
+fn parse_hex(s: &str) -> Option<usize> {
+    let digits = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    usize::from_str_radix(digits, 16).ok()
+}
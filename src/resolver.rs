@@ -0,0 +1,238 @@
+use crate::memory::PointerWidth;
+use crate::AnyResult;
+#[cfg(any(windows, target_os = "linux"))]
+use crate::HANDLE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Step {
+    #[doc = "`addr + n`"]
+    Add(isize),
+    #[doc = "`addr + insn_len + *(addr + disp_offset) as i32`"]
+    RipRelative { disp_offset: usize, insn_len: usize },
+    #[doc = "`E8 rel32`"]
+    FollowCall,
+    #[doc = "`E9 rel32` or `EB rel8`"]
+    FollowJmp,
+    #[doc = "Pointer at `addr`, as wide as the target's pointers"]
+    Deref,
+}
+
+#[doc = r#"Turns a scan result into the address that is actually wanted
+
+`Resolver::new().add(3).rip_relative(0, 4)` = "match + 3, read the rel32, add the instruction end""#]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Resolver {
+    steps: Vec<Step>,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn step(mut self, step: Step) -> Self {
+        self.steps.push(step);
+
+        self
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(self, n: isize) -> Self {
+        self.step(Step::Add(n))
+    }
+
+    pub fn rip_relative(self, disp_offset: usize, insn_len: usize) -> Self {
+        self.step(Step::RipRelative {
+            disp_offset,
+            insn_len,
+        })
+    }
+
+    pub fn follow_call(self) -> Self {
+        self.step(Step::FollowCall)
+    }
+
+    pub fn follow_jmp(self) -> Self {
+        self.step(Step::FollowJmp)
+    }
+
+    pub fn deref(self) -> Self {
+        self.step(Step::Deref)
+    }
+
+    #[inline]
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    #[doc = r"Return value: `Resolved address`
+
+`data` is a module image loaded at `base`, `offset` is a match inside it, `width` is the pointer size of its process"]
+    pub fn resolve_local(
+        &self,
+        data: &[u8],
+        base: usize,
+        offset: usize,
+        width: PointerWidth,
+    ) -> AnyResult<usize> {
+        self.resolve_with(base.wrapping_add(offset), width, |addr, buf| {
+            let start = addr.wrapping_sub(base);
+
+            match start
+                .checked_add(buf.len())
+                .and_then(|end| data.get(start..end))
+            {
+                Some(src) => {
+                    buf.copy_from_slice(src);
+
+                    Ok(())
+                }
+                None => Err(format!("{:#X} is outside of the buffer", addr).into()),
+            }
+        })
+    }
+
+    #[doc = r"Return value: `Resolved address`
+
+Memory is read through `mem`, `Deref` uses `mem.read_ptr`"]
+    pub fn resolve_mem<M: crate::memory::MemoryAccess + ?Sized>(
+        &self,
+        mem: &M,
+        addr: usize,
+    ) -> AnyResult<usize> {
+        self.resolve_steps(
+            addr,
+            mem.pointer_width(),
+            |addr, buf| mem.read_bytes(addr, buf),
+            |addr| mem.read_ptr(addr),
+        )
+    }
+
+    #[doc = r"Return value: `Resolved address`
+
+Memory is read from another process through `RemoteMemory`, pointers are as wide as `get_pointer_width` says.
+Fails if the width can't be detected"]
+    #[cfg(any(windows, target_os = "linux"))]
+    pub unsafe fn resolve_remote(
+        &self,
+        proc_handle: HANDLE,
+        addr: *const ::core::ffi::c_void,
+    ) -> AnyResult<*const ::core::ffi::c_void> {
+        let addr = self.resolve_mem(
+            &crate::memory::RemoteMemory::new(proc_handle)?,
+            addr as usize,
+        )?;
+
+        Ok(addr as *const ::core::ffi::c_void)
+    }
+
+    #[doc = r"Return value: `Resolved address`
+
+`read(addr, buf)` must fill the whole `buf`, `Deref` reads `width` bytes"]
+    pub fn resolve_with<F: FnMut(usize, &mut [u8]) -> AnyResult<()>>(
+        &self,
+        addr: usize,
+        width: PointerWidth,
+        mut read: F,
+    ) -> AnyResult<usize> {
+        // Both closures need `read`
+        let read = ::core::cell::RefCell::new(&mut read);
+
+        self.resolve_steps(
+            addr,
+            width,
+            |addr, buf| (*read.borrow_mut())(addr, buf),
+            |addr| {
+                let mut ptr = [0u8; 8];
+
+                (*read.borrow_mut())(addr, &mut ptr[..width.size()])?;
+
+                Ok(usize::try_from(u64::from_le_bytes(ptr))?)
+            },
+        )
+    }
+
+    // Addresses wrap at `width`
+    fn resolve_steps<R, P>(
+        &self,
+        mut addr: usize,
+        width: PointerWidth,
+        mut read: R,
+        mut read_ptr: P,
+    ) -> AnyResult<usize>
+    where
+        R: FnMut(usize, &mut [u8]) -> AnyResult<()>,
+        P: FnMut(usize) -> AnyResult<usize>,
+    {
+        for step in &self.steps {
+            addr = match *step {
+                Step::Add(n) => addr.wrapping_add_signed(n),
+                Step::RipRelative {
+                    disp_offset,
+                    insn_len,
+                } => {
+                    let mut disp = [0u8; 4];
+
+                    read(addr.wrapping_add(disp_offset), &mut disp)?;
+
+                    addr.wrapping_add(insn_len)
+                        .wrapping_add_signed(i32::from_le_bytes(disp) as isize)
+                }
+                Step::FollowCall => {
+                    let mut insn = [0u8; 5];
+
+                    read(addr, &mut insn)?;
+
+                    if insn[0] != 0xE8 {
+                        return Err(format!("no call at {:#X}", addr).into());
+                    }
+
+                    addr.wrapping_add(5)
+                        .wrapping_add_signed(i32::from_le_bytes([
+                            insn[1], insn[2], insn[3], insn[4],
+                        ]) as isize)
+                }
+                Step::FollowJmp => {
+                    let mut opcode = [0u8; 1];
+
+                    read(addr, &mut opcode)?;
+
+                    match opcode[0] {
+                        0xE9 => {
+                            let mut rel = [0u8; 4];
+
+                            read(addr.wrapping_add(1), &mut rel)?;
+
+                            addr.wrapping_add(5)
+                                .wrapping_add_signed(i32::from_le_bytes(rel) as isize)
+                        }
+                        0xEB => {
+                            let mut rel = [0u8; 1];
+
+                            read(addr.wrapping_add(1), &mut rel)?;
+
+                            addr.wrapping_add(2)
+                                .wrapping_add_signed(rel[0] as i8 as isize)
+                        }
+                        _ => return Err(format!("no jmp at {:#X}", addr).into()),
+                    }
+                }
+                Step::Deref => read_ptr(addr)?,
+            };
+
+            addr = width.wrap(addr);
+        }
+
+        Ok(addr)
+    }
+}
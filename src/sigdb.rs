@@ -0,0 +1,289 @@
+use crate::pattern::{Pattern, PatternSet};
+use crate::resolver::Resolver;
+use crate::AnyResult;
+#[cfg(any(windows, target_os = "linux"))]
+use crate::HANDLE;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SigEntry {
+    pub name: String,
+    pub module: String,
+    pub pattern: Pattern,
+    #[cfg_attr(feature = "serde", serde(default, rename = "steps"))]
+    pub resolver: Resolver,
+    #[doc = "Number of matches the pattern must have, `0` accepts any number"]
+    #[cfg_attr(feature = "serde", serde(default = "default_expected"))]
+    pub expected: usize,
+}
+
+#[cfg(feature = "serde")]
+fn default_expected() -> usize {
+    1
+}
+
+// Readable regions of `base..base + size`, the rest zeroed so offsets match the image
+#[cfg(any(windows, target_os = "linux"))]
+unsafe fn read_module(proc_handle: HANDLE, base: usize, size: usize) -> AnyResult<Vec<u8>> {
+    let end = base.checked_add(size).ok_or("module range overflows")?;
+
+    let mut buf: Vec<u8> = vec![0; size];
+    let mut any_read = false;
+
+    for region in crate::external::regions(proc_handle)?.readable() {
+        if region.base >= end {
+            break;
+        }
+
+        let start = region.base.max(base);
+        let stop = region.end().min(end);
+
+        if start >= stop {
+            continue;
+        }
+
+        // The query can be stale, skip regions that fail anyway
+        if let Ok(data) = crate::read_mem(
+            proc_handle,
+            start as *const ::core::ffi::c_void,
+            stop - start,
+        ) {
+            buf[start - base..stop - base].copy_from_slice(&data);
+
+            any_read = true;
+        }
+    }
+
+    if !any_read {
+        return Err(format!("no readable memory in {:#X}..{:#X}", base, end).into());
+    }
+
+    Ok(buf)
+}
+
+#[doc = r#"Signature database
+
+JSON layout (feature `json`):
+
+```json
+{
+    "signatures": [
+        {
+            "name": "local_player",
+            "module": "game.exe",
+            "pattern": "48 8B 05 ?? ?? ?? ?? 48 85 C0",
+            "steps": [{ "rip_relative": { "disp_offset": 3, "insn_len": 7 } }, "deref"],
+            "expected": 1
+        }
+    ]
+}
+```
+
+`steps` defaults to none and `expected` to 1"#]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SigDb {
+    pub signatures: Vec<SigEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SigFound {
+    pub name: String,
+    #[doc = "One resolved address per match"]
+    pub addrs: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SigAmbiguous {
+    pub name: String,
+    pub expected: usize,
+    #[doc = "Every match, relative to the module base"]
+    pub offsets: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SigFailed {
+    pub name: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SigReport {
+    pub found: Vec<SigFound>,
+    pub not_found: Vec<String>,
+    pub ambiguous: Vec<SigAmbiguous>,
+    #[doc = "Matched, but the module couldn't be read or a resolver step failed"]
+    pub failed: Vec<SigFailed>,
+}
+
+impl SigReport {
+    #[doc = "Return value: `Every signature resolved as expected?`"]
+    pub fn is_ok(&self) -> bool {
+        self.not_found.is_empty() && self.ambiguous.is_empty() && self.failed.is_empty()
+    }
+
+    #[doc = "Return value: `First resolved address of the signature`"]
+    pub fn get<S: AsRef<str>>(&self, name: S) -> Option<usize> {
+        self.found
+            .iter()
+            .find(|found| found.name == name.as_ref())
+            .and_then(|found| found.addrs.first().copied())
+    }
+
+    #[cfg(any(windows, target_os = "linux"))]
+    fn merge(&mut self, other: SigReport) {
+        self.found.extend(other.found);
+        self.not_found.extend(other.not_found);
+        self.ambiguous.extend(other.ambiguous);
+        self.failed.extend(other.failed);
+    }
+}
+
+impl SigDb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, entry: SigEntry) -> &mut Self {
+        self.signatures.push(entry);
+
+        self
+    }
+
+    #[cfg(feature = "json")]
+    pub fn from_json<S: AsRef<str>>(json: S) -> AnyResult<Self> {
+        Ok(serde_json::from_str(json.as_ref())?)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> AnyResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    #[doc = "Return value: `Distinct module names`"]
+    pub fn modules(&self) -> Vec<&str> {
+        let mut modules: Vec<&str> = Vec::new();
+
+        for entry in &self.signatures {
+            if !modules
+                .iter()
+                .any(|module| module.eq_ignore_ascii_case(&entry.module))
+            {
+                modules.push(&entry.module);
+            }
+        }
+
+        modules
+    }
+
+    #[doc = r"Return value: `SigReport`
+
+Resolves the signatures of `mod_name` against its image `data`, loaded at `base` in a process with pointers of `width`.
+Every pattern is found in a single pass"]
+    pub fn resolve_buffer<S: AsRef<str>>(
+        &self,
+        mod_name: S,
+        data: &[u8],
+        base: usize,
+        width: crate::memory::PointerWidth,
+    ) -> SigReport {
+        let entries: Vec<&SigEntry> = self
+            .signatures
+            .iter()
+            .filter(|entry| entry.module.eq_ignore_ascii_case(mod_name.as_ref()))
+            .collect();
+
+        let set: PatternSet = entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| (index.to_string(), entry.pattern.clone()))
+            .collect();
+
+        let mut offset_arrays = set.scan(data);
+
+        let mut report = SigReport::default();
+
+        for (index, entry) in entries.into_iter().enumerate() {
+            let offsets = offset_arrays.remove(&index.to_string()).unwrap_or_default();
+
+            if offsets.is_empty() {
+                report.not_found.push(entry.name.clone());
+            } else if entry.expected != 0 && offsets.len() != entry.expected {
+                report.ambiguous.push(SigAmbiguous {
+                    name: entry.name.clone(),
+                    expected: entry.expected,
+                    offsets,
+                });
+            } else {
+                match offsets
+                    .iter()
+                    .map(|offset| entry.resolver.resolve_local(data, base, *offset, width))
+                    .collect::<AnyResult<Vec<usize>>>()
+                {
+                    Ok(addrs) => report.found.push(SigFound {
+                        name: entry.name.clone(),
+                        addrs,
+                    }),
+                    Err(err) => report.failed.push(SigFailed {
+                        name: entry.name.clone(),
+                        error: err.to_string(),
+                    }),
+                }
+            }
+        }
+
+        report
+    }
+
+    #[doc = r"Return value: `SigReport`
+
+Every module is looked up with `get_mod_info` and read region by region,
+unreadable pages (guard, no-access, gaps between mappings) are left zeroed.
+If the pointer width can't be detected, every signature fails with that error"]
+    #[cfg(any(windows, target_os = "linux"))]
+    pub unsafe fn resolve_remote(&self, proc_handle: HANDLE, pid: u32) -> SigReport {
+        let mut report = SigReport::default();
+
+        let width = match crate::external::get_pointer_width(proc_handle) {
+            Ok(width) => width,
+            Err(err) => {
+                report
+                    .failed
+                    .extend(self.signatures.iter().map(|entry| SigFailed {
+                        name: entry.name.clone(),
+                        error: err.to_string(),
+                    }));
+
+                return report;
+            }
+        };
+
+        for module in self.modules() {
+            let data = crate::external::get_mod_info(pid, module).and_then(|mi| {
+                Ok((
+                    read_module(proc_handle, mi.addr as usize, mi.size as usize)?,
+                    mi.addr as usize,
+                ))
+            });
+
+            match data {
+                Ok((data, base)) => report.merge(self.resolve_buffer(module, &data, base, width)),
+                Err(err) => report.failed.extend(
+                    self.signatures
+                        .iter()
+                        .filter(|entry| entry.module.eq_ignore_ascii_case(module))
+                        .map(|entry| SigFailed {
+                            name: entry.name.clone(),
+                            error: err.to_string(),
+                        }),
+                ),
+            }
+        }
+
+        report
+    }
+}
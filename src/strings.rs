@@ -0,0 +1,235 @@
+use crate::memory::PointerWidth;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StrEncoding {
+    Ascii,
+    Utf16Le,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FoundStr {
+    pub offset: usize,
+    pub encoding: StrEncoding,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RefKind {
+    #[doc = "`lea reg, [rip+disp32]`, `lea reg, [disp32]` in 32-bit code"]
+    Lea,
+    #[doc = "`mov reg, [rip+disp32]`, `mov reg, [disp32]` in 32-bit code"]
+    Mov,
+    #[doc = "Little-endian pointer of the target's width"]
+    AbsPtr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StrRef {
+    #[doc = "Offset inside the code buffer"]
+    pub offset: usize,
+    #[doc = "Virtual address of the instruction or pointer"]
+    pub addr: usize,
+    pub kind: RefKind,
+}
+
+#[inline]
+fn is_printable(byte: u8) -> bool {
+    byte == b'\t' || (0x20..0x7F).contains(&byte)
+}
+
+#[doc = r"Return value: `Vec<FoundStr>`, sorted by offset
+
+Printable ASCII runs and UTF-16LE runs of printable ASCII characters with at least `min_len` characters, like `strings`"]
+pub fn find_strings(data: &[u8], min_len: usize) -> Vec<FoundStr> {
+    let min_len = min_len.max(1);
+
+    let mut found: Vec<FoundStr> = Vec::new();
+
+    let mut start = 0;
+
+    for i in 0..=data.len() {
+        if i < data.len() && is_printable(data[i]) {
+            continue;
+        }
+
+        if i - start >= min_len {
+            found.push(FoundStr {
+                offset: start,
+                encoding: StrEncoding::Ascii,
+                text: String::from_utf8_lossy(&data[start..i]).into_owned(),
+            });
+        }
+
+        start = i + 1;
+    }
+
+    // Both byte alignments, a UTF-16 string isn't necessarily 2-aligned
+    for parity in 0..2 {
+        let units: Vec<u16> = data[parity.min(data.len())..]
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+
+        let mut start = 0;
+
+        for i in 0..=units.len() {
+            if i < units.len() && units[i] < 0x80 && is_printable(units[i] as u8) {
+                continue;
+            }
+
+            if i - start >= min_len {
+                found.push(FoundStr {
+                    offset: parity + start * 2,
+                    encoding: StrEncoding::Utf16Le,
+                    text: String::from_utf16_lossy(&units[start..i]),
+                });
+            }
+
+            start = i + 1;
+        }
+    }
+
+    found.sort();
+
+    found
+}
+
+#[doc = r"Return value: `Vec<Offset>` of every NUL-terminated occurrence of `text`"]
+pub fn find_str<S: AsRef<str>>(data: &[u8], text: S, encoding: StrEncoding) -> Vec<usize> {
+    let mut bytes: Vec<u8> = match encoding {
+        StrEncoding::Ascii => text.as_ref().as_bytes().to_vec(),
+        StrEncoding::Utf16Le => text
+            .as_ref()
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect(),
+    };
+
+    match encoding {
+        StrEncoding::Ascii => bytes.push(0),
+        StrEncoding::Utf16Le => bytes.extend_from_slice(&[0, 0]),
+    }
+
+    literal_scan(data, &bytes)
+}
+
+#[doc = r"Return value: `Vec<StrRef>`, sorted by offset
+
+`code` is loaded at `code_base` in a process with pointers of `width`;
+finds `lea`/`mov` (RIP-relative in 64-bit code, absolute in 32-bit code) and pointers whose target is `target`"]
+pub fn find_refs(code: &[u8], code_base: usize, target: usize, width: PointerWidth) -> Vec<StrRef> {
+    let mut refs: Vec<StrRef> = Vec::new();
+    let mut insn_ranges: Vec<::core::ops::Range<usize>> = Vec::new();
+
+    for j in 0..code.len().saturating_sub(5) {
+        let kind = match code[j] {
+            0x8D => RefKind::Lea,
+            0x8B => RefKind::Mov,
+            _ => continue,
+        };
+
+        // mod = 00, rm = 101
+        if code[j + 1] & 0xC7 != 0x05 {
+            continue;
+        }
+
+        // 40-4F are `inc`/`dec` in 32-bit code, not REX
+        let start = match j.checked_sub(1) {
+            Some(rex) if PointerWidth::Bits64 == width && (0x40..=0x4F).contains(&code[rex]) => rex,
+            _ => j,
+        };
+
+        let Some(insn) = crate::x86::decode(&code[start..]) else {
+            continue;
+        };
+
+        let Some(disp) = insn.disp.filter(|_| insn.rip_relative) else {
+            continue;
+        };
+
+        let disp = i32::from_le_bytes(
+            code[start + disp.offset..start + disp.offset + 4]
+                .try_into()
+                .unwrap(),
+        );
+
+        let addr = code_base.wrapping_add(start);
+
+        let disp_target = match width {
+            PointerWidth::Bits32 => disp as u32 as usize,
+            PointerWidth::Bits64 => addr
+                .wrapping_add(insn.len)
+                .wrapping_add_signed(disp as isize),
+        };
+
+        if disp_target == target {
+            refs.push(StrRef {
+                offset: start,
+                addr,
+                kind,
+            });
+
+            insn_ranges.push(start..start + insn.len);
+        }
+    }
+
+    let ptr_bytes = match width {
+        PointerWidth::Bits32 => match u32::try_from(target) {
+            Ok(target) => target.to_le_bytes().to_vec(),
+            // Not expressible as a 32-bit pointer
+            Err(_) => Vec::new(),
+        },
+        PointerWidth::Bits64 => (target as u64).to_le_bytes().to_vec(),
+    };
+
+    refs.extend(
+        literal_scan(code, &ptr_bytes)
+            .into_iter()
+            // An absolute operand of an instruction found above is not a separate reference
+            .filter(|offset| !insn_ranges.iter().any(|range| range.contains(offset)))
+            .map(|offset| StrRef {
+                offset,
+                addr: code_base.wrapping_add(offset),
+                kind: RefKind::AbsPtr,
+            }),
+    );
+
+    refs.sort();
+
+    refs
+}
+
+#[doc = r"Return value: `Vec<(FoundStr, Vec<StrRef>)>`
+
+Finds `text` (ASCII and UTF-16LE) in a module image loaded at `base`, usually `ModInfo::addr`, and the code in the same image that references it.
+`width` is the pointer size of the process the image belongs to"]
+pub fn find_str_refs<S: AsRef<str>>(
+    data: &[u8],
+    base: usize,
+    text: S,
+    width: PointerWidth,
+) -> Vec<(FoundStr, Vec<StrRef>)> {
+    let mut result: Vec<(FoundStr, Vec<StrRef>)> = Vec::new();
+
+    for encoding in [StrEncoding::Ascii, StrEncoding::Utf16Le] {
+        for offset in find_str(data, text.as_ref(), encoding) {
+            result.push((
+                FoundStr {
+                    offset,
+                    encoding,
+                    text: text.as_ref().to_owned(),
+                },
+                find_refs(data, base, base.wrapping_add(offset), width),
+            ));
+        }
+    }
+
+    result
+}
+
+fn literal_scan(data: &[u8], bytes: &[u8]) -> Vec<usize> {
+    match crate::pattern::Pattern::from_code(bytes, "x".repeat(bytes.len())) {
+        Ok(pat) => pat.scan(data),
+        Err(_) => Vec::new(),
+    }
+}
@@ -63,11 +63,30 @@ pub mod mem_free {
     pub const RELEASE: u32 = 0x00008000;
 }
 
+#[doc = "Constant collection"]
+pub mod mem_type {
+    pub const IMAGE: u32 = 0x0100_0000;
+
+    pub const MAPPED: u32 = 0x0004_0000;
+
+    pub const PRIVATE: u32 = 0x0002_0000;
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ProcInfo {
+    #[doc = "On Linux: the Windows exe name for Wine/Proton processes, otherwise the file name of `exe`, falling back to `comm`"]
     pub name: String,
     pub id: u32,
+    #[doc = "`/proc/<pid>/comm`, at most 15 bytes"]
+    #[cfg(target_os = "linux")]
+    pub comm: String,
+    #[doc = "Target of `/proc/<pid>/exe`, empty if it can't be read"]
+    #[cfg(target_os = "linux")]
+    pub exe: String,
+    #[doc = "`/proc/<pid>/cmdline`"]
+    #[cfg(target_os = "linux")]
+    pub cmdline: Vec<String>,
 }
 
 #[repr(C)]
@@ -87,6 +106,82 @@ pub struct MemInfo {
     pub region_size: usize,
 }
 
+#[doc = r"One region of `external::regions`
+
+On Linux every mapping is a region: `---` mappings are `mem_alloc::RESERVE`, anonymous ones `mem_type::PRIVATE` and file mappings `mem_type::IMAGE` when some mapping of the file is executable, `mem_type::MAPPED` otherwise"]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MemRegion {
+    pub base: usize,
+    pub size: usize,
+    #[doc = "Base of the allocation, the module base for images"]
+    pub alloc_base: usize,
+    #[doc = "`mem_protect` at allocation time, on Linux the maximum protection from `smaps`"]
+    pub alloc_protect: u32,
+    #[doc = "`mem_protect`"]
+    pub protect: u32,
+    #[doc = "`mem_alloc::COMMIT` or `mem_alloc::RESERVE`"]
+    pub state: u32,
+    #[doc = "`mem_type`"]
+    pub type_: u32,
+    #[doc = "Backing file, empty for private memory; a `\\Device\\...` path on Windows"]
+    pub path: String,
+}
+
+impl MemRegion {
+    #[inline]
+    pub fn end(&self) -> usize {
+        self.base + self.size
+    }
+
+    #[inline]
+    pub fn contains(&self, addr: usize) -> bool {
+        self.base <= addr && addr < self.end()
+    }
+
+    #[inline]
+    pub fn is_committed(&self) -> bool {
+        self.state == mem_alloc::COMMIT
+    }
+
+    pub fn is_readable(&self) -> bool {
+        self.is_committed()
+            && self.protect & mem_protect::GUARD == 0
+            && self.protect
+                & (mem_protect::READONLY
+                    | mem_protect::READ_WRITE
+                    | mem_protect::WRITECOPY
+                    | mem_protect::EXECUTE_READ
+                    | mem_protect::EXECUTE_READ_WRITE
+                    | mem_protect::EXECUTE_WRITECOPY)
+                != 0
+    }
+
+    pub fn is_writable(&self) -> bool {
+        self.is_committed()
+            && self.protect & (mem_protect::READ_WRITE | mem_protect::EXECUTE_READ_WRITE) != 0
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.is_committed()
+            && self.protect
+                & (mem_protect::EXECUTE
+                    | mem_protect::EXECUTE_READ
+                    | mem_protect::EXECUTE_READ_WRITE
+                    | mem_protect::EXECUTE_WRITECOPY)
+                != 0
+    }
+
+    #[inline]
+    pub fn is_private(&self) -> bool {
+        self.type_ == mem_type::PRIVATE
+    }
+
+    #[inline]
+    pub fn is_image(&self) -> bool {
+        self.type_ == mem_type::IMAGE
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum MemQueryProtect {
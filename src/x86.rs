@@ -0,0 +1,342 @@
+#[doc = "Location of an operand inside the instruction bytes"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Operand {
+    pub offset: usize,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OpcodeMap {
+    #[doc = "One byte opcodes"]
+    Primary,
+    #[doc = "`0F xx`"]
+    Map0F,
+    #[doc = "`0F 38 xx`"]
+    Map0F38,
+    #[doc = "`0F 3A xx`"]
+    Map0F3A,
+}
+
+#[doc = "Decoded x86-64 instruction layout"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Insn {
+    pub len: usize,
+    pub map: OpcodeMap,
+    pub opcode: u8,
+    #[doc = "Offset of the opcode byte"]
+    pub opcode_offset: usize,
+    pub rex: Option<u8>,
+    pub modrm: Option<u8>,
+    #[doc = "Memory displacement"]
+    pub disp: Option<Operand>,
+    #[doc = "`disp` is relative to the end of the instruction"]
+    pub rip_relative: bool,
+    #[doc = "`disp` is an absolute address: no base and no index register, or `moffs`"]
+    pub absolute: bool,
+    pub imm: Option<Operand>,
+    #[doc = "Branch displacement, relative to the end of the instruction"]
+    pub rel: Option<Operand>,
+}
+
+#[derive(Clone, Copy)]
+enum Imm {
+    None,
+    Byte,
+    Word,
+    // 2 bytes with the `66` prefix, 4 otherwise
+    WordOrDword,
+    // `B8+r`: 8 bytes with `REX.W`
+    WordDwordOrQword,
+    // `enter imm16, imm8`
+    Enter,
+    Rel8,
+    Rel32,
+    // `A0`-`A3`: address sized
+    Moffs,
+}
+
+#[doc = r"Return value: `Insn`, `None` if the bytes are not a valid instruction
+
+Decodes in 64-bit mode"]
+pub fn decode(code: &[u8]) -> Option<Insn> {
+    let mut i = 0;
+
+    let mut operand_size_prefix = false;
+    let mut address_size_prefix = false;
+
+    loop {
+        match *code.get(i)? {
+            0x66 => operand_size_prefix = true,
+            0x67 => address_size_prefix = true,
+            0xF0 | 0xF2 | 0xF3 | 0x2E | 0x36 | 0x3E | 0x26 | 0x64 | 0x65 => {}
+            _ => break,
+        }
+
+        i += 1;
+
+        if i > 14 {
+            return None;
+        }
+    }
+
+    let mut rex = None;
+
+    if let 0x40..=0x4F = *code.get(i)? {
+        rex = Some(code[i]);
+        i += 1;
+    }
+
+    let rex_w = rex.is_some_and(|rex| rex & 0x8 != 0);
+
+    let map;
+    let opcode;
+    let opcode_offset;
+    let has_modrm;
+    let mut imm;
+
+    match *code.get(i)? {
+        // VEX/EVEX, always ModRM, only an occasional imm8
+        prefix @ (0xC4 | 0xC5 | 0x62) => {
+            if rex.is_some() {
+                return None;
+            }
+
+            let (map_select, prefix_len) = match prefix {
+                0xC5 => (1, 2),
+                0xC4 => (*code.get(i + 1)? & 0x1F, 3),
+                _ => (*code.get(i + 1)? & 0x07, 4),
+            };
+
+            map = match map_select {
+                1 => OpcodeMap::Map0F,
+                2 => OpcodeMap::Map0F38,
+                3 => OpcodeMap::Map0F3A,
+                _ => return None,
+            };
+
+            i += prefix_len;
+
+            opcode = *code.get(i)?;
+            opcode_offset = i;
+            i += 1;
+
+            // vzeroupper/vzeroall
+            has_modrm = !(map == OpcodeMap::Map0F && opcode == 0x77);
+
+            imm = match map {
+                OpcodeMap::Map0F3A => Imm::Byte,
+                OpcodeMap::Map0F => match opcode {
+                    0x70..=0x73 | 0xC2 | 0xC4..=0xC6 => Imm::Byte,
+                    _ => Imm::None,
+                },
+                _ => Imm::None,
+            };
+        }
+        0x0F => match *code.get(i + 1)? {
+            0x38 => {
+                map = OpcodeMap::Map0F38;
+                opcode = *code.get(i + 2)?;
+                opcode_offset = i + 2;
+                i += 3;
+                has_modrm = true;
+                imm = Imm::None;
+            }
+            0x3A => {
+                map = OpcodeMap::Map0F3A;
+                opcode = *code.get(i + 2)?;
+                opcode_offset = i + 2;
+                i += 3;
+                has_modrm = true;
+                imm = Imm::Byte;
+            }
+            op => {
+                map = OpcodeMap::Map0F;
+                opcode = op;
+                opcode_offset = i + 1;
+                i += 2;
+                (has_modrm, imm) = layout_0f(op);
+            }
+        },
+        op => {
+            map = OpcodeMap::Primary;
+            opcode = op;
+            opcode_offset = i;
+            i += 1;
+            (has_modrm, imm) = layout_primary(op)?;
+        }
+    }
+
+    let mut modrm = None;
+    let mut disp = None;
+    let mut rip_relative = false;
+    let mut absolute = false;
+
+    if has_modrm {
+        let byte = *code.get(i)?;
+        modrm = Some(byte);
+        i += 1;
+
+        let mod_ = byte >> 6;
+        let reg = (byte >> 3) & 7;
+        let rm = byte & 7;
+
+        // test r/m, imm
+        if map == OpcodeMap::Primary && reg <= 1 {
+            match opcode {
+                0xF6 => imm = Imm::Byte,
+                0xF7 => imm = Imm::WordOrDword,
+                _ => {}
+            }
+        }
+
+        if mod_ != 3 {
+            let mut disp_size = match mod_ {
+                1 => 1,
+                2 => 4,
+                _ => 0,
+            };
+
+            if rm == 4 {
+                let sib = *code.get(i)?;
+                i += 1;
+
+                if mod_ == 0 && sib & 7 == 5 {
+                    disp_size = 4;
+                    // no base, and index 4 without REX.X means no index either
+                    absolute = (sib >> 3) & 7 == 4 && rex.is_none_or(|rex| rex & 0x2 == 0);
+                }
+            } else if mod_ == 0 && rm == 5 {
+                disp_size = 4;
+                rip_relative = true;
+            }
+
+            if disp_size != 0 {
+                disp = Some(Operand {
+                    offset: i,
+                    size: disp_size,
+                });
+                i += disp_size;
+            }
+        }
+    }
+
+    let mut rel = None;
+
+    let imm_size = match imm {
+        Imm::None => 0,
+        Imm::Byte => 1,
+        Imm::Word => 2,
+        Imm::WordOrDword if operand_size_prefix && !rex_w => 2,
+        Imm::WordOrDword => 4,
+        Imm::WordDwordOrQword if rex_w => 8,
+        Imm::WordDwordOrQword if operand_size_prefix => 2,
+        Imm::WordDwordOrQword => 4,
+        Imm::Enter => 3,
+        Imm::Rel8 | Imm::Rel32 => {
+            let size = if let Imm::Rel8 = imm { 1 } else { 4 };
+
+            rel = Some(Operand { offset: i, size });
+            i += size;
+
+            0
+        }
+        Imm::Moffs => {
+            let size = if address_size_prefix { 4 } else { 8 };
+
+            disp = Some(Operand { offset: i, size });
+            absolute = true;
+            i += size;
+
+            0
+        }
+    };
+
+    let imm = if imm_size != 0 {
+        let operand = Operand {
+            offset: i,
+            size: imm_size,
+        };
+        i += imm_size;
+
+        Some(operand)
+    } else {
+        None
+    };
+
+    if i > code.len() || i > 15 {
+        return None;
+    }
+
+    Some(Insn {
+        len: i,
+        map,
+        opcode,
+        opcode_offset,
+        rex,
+        modrm,
+        disp,
+        rip_relative,
+        absolute,
+        imm,
+        rel,
+    })
+}
+
+// Return value: `(Has ModRM, Immediate)`, `None` for opcodes invalid in 64-bit mode
+fn layout_primary(op: u8) -> Option<(bool, Imm)> {
+    Some(match op {
+        0x06 | 0x07 | 0x0E | 0x16 | 0x17 | 0x1E | 0x1F | 0x27 | 0x2F | 0x37 | 0x3F => return None,
+        0x60 | 0x61 | 0x82 | 0x9A | 0xCE | 0xD4 | 0xD5 | 0xD6 | 0xEA => return None,
+        0x00..=0x3F => match op & 7 {
+            0..=3 => (true, Imm::None),
+            4 => (false, Imm::Byte),
+            5 => (false, Imm::WordOrDword),
+            _ => (false, Imm::None),
+        },
+        0x50..=0x5F => (false, Imm::None),
+        0x63 => (true, Imm::None),
+        0x68 => (false, Imm::WordOrDword),
+        0x69 => (true, Imm::WordOrDword),
+        0x6A => (false, Imm::Byte),
+        0x6B => (true, Imm::Byte),
+        0x6C..=0x6F => (false, Imm::None),
+        0x70..=0x7F => (false, Imm::Rel8),
+        0x80 | 0x83 => (true, Imm::Byte),
+        0x81 => (true, Imm::WordOrDword),
+        0x84..=0x8F => (true, Imm::None),
+        0x90..=0x9F => (false, Imm::None),
+        0xA0..=0xA3 => (false, Imm::Moffs),
+        0xA8 => (false, Imm::Byte),
+        0xA9 => (false, Imm::WordOrDword),
+        0xA4..=0xAF => (false, Imm::None),
+        0xB0..=0xB7 => (false, Imm::Byte),
+        0xB8..=0xBF => (false, Imm::WordDwordOrQword),
+        0xC0 | 0xC1 | 0xC6 => (true, Imm::Byte),
+        0xC7 => (true, Imm::WordOrDword),
+        0xC2 | 0xCA => (false, Imm::Word),
+        0xC8 => (false, Imm::Enter),
+        0xCD => (false, Imm::Byte),
+        0xC3 | 0xC9 | 0xCB | 0xCC | 0xCF => (false, Imm::None),
+        0xD0..=0xD3 | 0xD8..=0xDF => (true, Imm::None),
+        0xD7 => (false, Imm::None),
+        0xE0..=0xE3 | 0xEB => (false, Imm::Rel8),
+        0xE4..=0xE7 => (false, Imm::Byte),
+        0xE8 | 0xE9 => (false, Imm::Rel32),
+        0xEC..=0xEF | 0xF1 | 0xF4 | 0xF5 | 0xF8..=0xFD => (false, Imm::None),
+        0xF6 | 0xF7 | 0xFE | 0xFF => (true, Imm::None),
+        // Prefixes and escapes are consumed before the opcode
+        _ => return None,
+    })
+}
+
+// Return value: `(Has ModRM, Immediate)`
+fn layout_0f(op: u8) -> (bool, Imm) {
+    match op {
+        0x05..=0x0B | 0x0E | 0x30..=0x37 | 0x77 | 0xA0..=0xA2 | 0xA8..=0xAA | 0xC8..=0xCF => {
+            (false, Imm::None)
+        }
+        0x0F | 0x70..=0x73 | 0xA4 | 0xAC | 0xBA | 0xC2 | 0xC4..=0xC6 => (true, Imm::Byte),
+        0x80..=0x8F => (false, Imm::Rel32),
+        _ => (true, Imm::None),
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg(windows)]
+
 #[test]
 fn get_all_proc_info() {
     unsafe {
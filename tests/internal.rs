@@ -1,3 +1,5 @@
+#![cfg(windows)]
+
 #[test]
 fn get_mod_info() {
     unsafe {
@@ -0,0 +1,535 @@
+#![cfg(target_os = "linux")]
+
+#[test]
+fn read_write_mem() {
+    unsafe {
+        let proc_handle = vcheat::external::open_proc(::std::process::id()).unwrap();
+
+        let mut value: u64 = 0x1122_3344_5566_7788;
+
+        let addr = ::core::ptr::addr_of_mut!(value).cast::<::core::ffi::c_void>();
+
+        assert_eq!(
+            vcheat::read_mem(proc_handle, addr, 8).unwrap(),
+            0x1122_3344_5566_7788_u64.to_ne_bytes()
+        );
+
+        assert_eq!(
+            vcheat::write_mem_hex_str(proc_handle, addr, "AA BB").unwrap(),
+            2
+        );
+
+        let mut read_back: u64 = 0;
+
+        assert_eq!(
+            vcheat::read_mem_t(proc_handle, addr, &mut read_back, 8).unwrap(),
+            8
+        );
+
+        assert_eq!(read_back.to_ne_bytes()[..2], [0xAA, 0xBB]);
+
+        assert!(vcheat::read_mem(proc_handle, ::core::ptr::null(), 8).is_err());
+
+        vcheat::external::write(proc_handle, addr, &[0x1234u16, 0x5678]).unwrap();
+
+        assert_eq!(
+            vcheat::external::read::<u32>(proc_handle, addr).unwrap(),
+            0x5678_1234
+        );
+        assert_eq!(
+            vcheat::external::read_slice::<u16>(proc_handle, addr, 2).unwrap(),
+            vec![0x1234, 0x5678]
+        );
+        assert_eq!(
+            vcheat::external::read(
+                proc_handle,
+                vcheat::memory::RemotePtr::<u16>::from(addr).offset(1)
+            )
+            .unwrap(),
+            0x5678
+        );
+        assert!(vcheat::external::read::<u64>(proc_handle, ::core::ptr::null()).is_err());
+
+        vcheat::external::close_handle(proc_handle).unwrap();
+    }
+}
+
+#[test]
+fn write_read_only_page() {
+    unsafe {
+        let proc_handle = vcheat::external::open_proc(::std::process::id()).unwrap();
+
+        // PROT_READ, MAP_PRIVATE | MAP_ANONYMOUS
+        let page = mmap(::core::ptr::null_mut(), 0x1000, 1, 0x22, -1, 0);
+
+        assert_ne!(page as isize, -1);
+
+        let addr = page.cast::<::core::ffi::c_void>();
+
+        assert_eq!(
+            vcheat::write_mem(proc_handle, addr, &[0x55u8, 0x66]).unwrap(),
+            2
+        );
+
+        assert_eq!(
+            vcheat::read_mem(proc_handle, addr, 4).unwrap(),
+            vec![0x55, 0x66, 0x00, 0x00]
+        );
+
+        munmap(page, 0x1000);
+    }
+}
+
+#[test]
+fn get_all_proc_info() {
+    unsafe {
+        let procs_info = vcheat::external::get_all_proc_info().unwrap();
+
+        let pi = procs_info
+            .iter()
+            .find(|pi| pi.id == ::std::process::id())
+            .unwrap();
+
+        let exe = ::std::env::current_exe().unwrap();
+
+        assert_eq!(pi.exe, exe.to_str().unwrap());
+        assert_eq!(pi.name, exe.file_name().unwrap().to_str().unwrap());
+        assert!(pi.name.starts_with(&pi.comm));
+    }
+}
+
+#[test]
+fn get_pid_wine() {
+    use std::os::unix::process::CommandExt;
+
+    let mut child = ::std::process::Command::new("sleep")
+        .arg0("Z:\\games\\Game.exe")
+        .arg("5")
+        .spawn()
+        .unwrap();
+
+    // The child may not have exec'd yet
+    let pid = (0..100).find_map(|_| {
+        ::std::thread::sleep(::std::time::Duration::from_millis(10));
+
+        unsafe { vcheat::external::get_pid("game.EXE").ok() }
+    });
+
+    assert_eq!(pid, Some(child.id()));
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn get_mod_info() {
+    unsafe {
+        let pid = ::std::process::id();
+
+        let exe = ::std::env::current_exe().unwrap();
+
+        let mi = vcheat::external::get_mod_info(
+            pid,
+            exe.file_name().unwrap().to_str().unwrap().to_uppercase(),
+        )
+        .unwrap();
+
+        let addr = get_mod_info as *const () as usize;
+
+        assert!(mi.addr as usize <= addr && addr < mi.addr as usize + mi.size as usize);
+        assert_eq!(mi.handle, mi.addr as vcheat::HANDLE);
+
+        assert!(vcheat::external::get_all_mod_info(pid)
+            .unwrap()
+            .contains(&mi));
+    }
+}
+
+extern "C" {
+    fn mmap(addr: *mut u8, len: usize, prot: i32, flags: i32, fd: i32, offset: i64) -> *mut u8;
+
+    fn munmap(addr: *mut u8, len: usize) -> i32;
+
+    fn mprotect(addr: *mut u8, len: usize, prot: i32) -> i32;
+}
+
+#[test]
+fn get_mod_info_pe() {
+    use std::os::fd::AsRawFd;
+
+    // Headers only, `SizeOfImage` = 0x5000
+    let mut image = vec![0u8; 0x1000];
+
+    image[..2].copy_from_slice(b"MZ");
+    image[0x3C..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+    image[0x80..0x84].copy_from_slice(b"PE\0\0");
+    image[0x80 + 0x50..0x80 + 0x54].copy_from_slice(&0x5000u32.to_le_bytes());
+
+    let path = ::std::env::temp_dir().join(format!("vcheat_{}_Fake.exe", ::std::process::id()));
+
+    ::std::fs::write(&path, &image).unwrap();
+
+    let file = ::std::fs::File::open(&path).unwrap();
+
+    unsafe {
+        // PROT_READ, MAP_PRIVATE
+        let base = mmap(::core::ptr::null_mut(), 0x1000, 1, 2, file.as_raw_fd(), 0);
+
+        assert_ne!(base as isize, -1);
+
+        let mi = vcheat::external::get_mod_info(
+            ::std::process::id(),
+            path.file_name().unwrap().to_str().unwrap().to_lowercase(),
+        )
+        .unwrap();
+
+        assert_eq!(mi.addr as usize, base as usize);
+        assert_eq!(mi.size, 0x5000);
+
+        munmap(base, 0x1000);
+    }
+
+    ::std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn sigdb_resolve_remote() {
+    use std::os::fd::AsRawFd;
+
+    // Three pages, `SizeOfImage` = 0x3000, the middle one made inaccessible below
+    let mut image = vec![0u8; 0x3000];
+
+    image[..2].copy_from_slice(b"MZ");
+    image[0x3C..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+    image[0x80..0x84].copy_from_slice(b"PE\0\0");
+    image[0x80 + 0x50..0x80 + 0x54].copy_from_slice(&0x3000u32.to_le_bytes());
+    image[0x2100..0x2108].copy_from_slice(&[0x5A, 0xC3, 0x11, 0xE7, 0x9D, 0x04, 0x6B, 0xF2]);
+
+    let path = ::std::env::temp_dir().join(format!("vcheat_{}_Packed.exe", ::std::process::id()));
+
+    ::std::fs::write(&path, &image).unwrap();
+
+    let file = ::std::fs::File::open(&path).unwrap();
+
+    let mod_name = path.file_name().unwrap().to_str().unwrap().to_owned();
+
+    let mut db = vcheat::sigdb::SigDb::new();
+
+    db.add(vcheat::sigdb::SigEntry {
+        name: "marker".to_owned(),
+        module: mod_name.clone(),
+        pattern: vcheat::pattern::Pattern::parse("5A C3 11 E7 9D 04 6B F2").unwrap(),
+        resolver: Default::default(),
+        expected: 1,
+    });
+
+    unsafe {
+        // PROT_READ, MAP_PRIVATE
+        let base = mmap(::core::ptr::null_mut(), 0x3000, 1, 2, file.as_raw_fd(), 0);
+
+        assert_ne!(base as isize, -1);
+
+        // PROT_NONE
+        assert_eq!(mprotect(base.add(0x1000), 0x1000, 0), 0);
+
+        let pid = ::std::process::id();
+        let proc_handle = vcheat::external::open_proc(pid).unwrap();
+
+        let report = db.resolve_remote(proc_handle, pid);
+
+        assert!(report.is_ok(), "{:?}", report);
+        assert_eq!(report.get("marker"), Some(base as usize + 0x2100));
+
+        vcheat::external::close_handle(proc_handle).unwrap();
+
+        munmap(base, 0x3000);
+    }
+
+    ::std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn regions() {
+    unsafe {
+        let proc_handle = vcheat::external::open_proc(::std::process::id()).unwrap();
+
+        let stack_var = 0u64;
+        let stack_addr = ::core::ptr::addr_of!(stack_var) as usize;
+        let code_addr = regions as *const () as usize;
+
+        let all: Vec<vcheat::types::MemRegion> =
+            vcheat::external::regions(proc_handle).unwrap().collect();
+
+        assert!(all.windows(2).all(|pair| pair[0].end() <= pair[1].base));
+
+        let stack = all
+            .iter()
+            .find(|region| region.contains(stack_addr))
+            .unwrap();
+
+        assert!(stack.is_readable() && stack.is_writable() && stack.is_private());
+        assert!(!stack.is_executable());
+
+        let mi = vcheat::memory::MemoryAccess::query(
+            &vcheat::memory::RemoteMemory::new(proc_handle).unwrap(),
+            stack_addr,
+        )
+        .unwrap();
+
+        assert_eq!(mi.protect, vcheat::types::mem_protect::READ_WRITE);
+        assert_eq!(mi.region_size, stack.end() - (stack_addr & !0xFFF));
+
+        let remote = vcheat::memory::RemoteMemory::new(proc_handle).unwrap();
+
+        // Mapped at startup, other tests can't change them
+        let exe = ::std::env::current_exe().unwrap();
+
+        let image: Vec<&vcheat::types::MemRegion> = all
+            .iter()
+            .filter(|region| ::std::path::Path::new(&region.path) == exe)
+            .collect();
+
+        assert!(!image.is_empty());
+
+        for region in image {
+            let mi = vcheat::memory::MemoryAccess::query(&remote, region.base).unwrap();
+
+            assert_eq!(
+                (mi.protect, mi.state, mi.region_size),
+                (region.protect, region.state, region.size)
+            );
+        }
+
+        assert!(vcheat::memory::MemoryAccess::query(&remote, 0).is_err());
+
+        let code = vcheat::external::regions(proc_handle)
+            .unwrap()
+            .executable()
+            .image()
+            .find(|region| region.contains(code_addr))
+            .unwrap();
+
+        let mi = vcheat::external::get_mod_info(
+            ::std::process::id(),
+            ::std::env::current_exe()
+                .unwrap()
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(code.alloc_base, mi.addr as usize);
+        assert_eq!(
+            code.path,
+            ::std::env::current_exe().unwrap().to_str().unwrap()
+        );
+        assert_eq!(code.state, vcheat::types::mem_alloc::COMMIT);
+
+        assert!(vcheat::external::regions(proc_handle)
+            .unwrap()
+            .writable()
+            .private()
+            .all(|region| region.is_writable() && region.is_private()));
+    }
+}
+
+#[test]
+fn read_batch() {
+    unsafe {
+        let proc_handle = vcheat::external::open_proc(::std::process::id()).unwrap();
+
+        let values: Vec<u32> = (0..3000).collect();
+
+        let mut bufs = vec![[0u8; 4]; values.len() + 1];
+
+        let mut requests: Vec<vcheat::memory::ReadRequest> = bufs
+            .iter_mut()
+            .enumerate()
+            .map(|(i, buf)| {
+                // One unreadable request in the middle of the second batch
+                let addr = match i {
+                    1500 => 0,
+                    3000 => values.as_ptr() as usize,
+                    _ => values.as_ptr().add(i) as usize,
+                };
+
+                vcheat::memory::ReadRequest::new(addr, buf)
+            })
+            .collect();
+
+        let results = vcheat::external::read_batch(proc_handle, &mut requests);
+
+        assert_eq!(results.len(), 3001);
+        assert!(results[1500].is_err());
+        assert!(results
+            .iter()
+            .enumerate()
+            .all(|(i, result)| i == 1500 || result.is_ok()));
+
+        drop(requests);
+
+        for (i, buf) in bufs.iter().enumerate() {
+            match i {
+                1500 => assert_eq!(*buf, [0; 4]),
+                3000 => assert_eq!(u32::from_ne_bytes(*buf), 0),
+                _ => assert_eq!(u32::from_ne_bytes(*buf), i as u32),
+            }
+        }
+    }
+}
+
+#[test]
+fn read_multi_pointer() {
+    unsafe {
+        let proc_handle = vcheat::external::open_proc(::std::process::id()).unwrap();
+
+        let target: [usize; 2] = [0, 0xDEAD];
+        let middle: [usize; 3] = [0, 0, target.as_ptr() as usize];
+        let base: usize = middle.as_ptr() as usize;
+
+        let final_ptr = vcheat::external::read_multi_pointer(
+            proc_handle,
+            ::core::ptr::addr_of!(base).cast::<::core::ffi::c_void>(),
+            &[
+                2 * ::core::mem::size_of::<usize>() as isize,
+                ::core::mem::size_of::<usize>() as isize,
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(final_ptr as usize, 0xDEAD);
+
+        let err = vcheat::external::read_multi_pointer(
+            proc_handle,
+            ::core::ptr::addr_of!(base).cast::<::core::ffi::c_void>(),
+            &[2 * ::core::mem::size_of::<usize>() as isize, 8, 0x10],
+        )
+        .unwrap_err();
+
+        let err = err
+            .downcast_ref::<vcheat::memory::PointerChainError>()
+            .unwrap();
+
+        assert_eq!((err.hop, err.addr), (3, 0xDEAD + 0x10));
+    }
+}
+
+static POINTER_TARGET: [u32; 2] = [0, 0xC0FFEE];
+static POINTER_SLOT: &[u32; 2] = &POINTER_TARGET;
+
+#[test]
+fn resolve_pointer_path() {
+    unsafe {
+        let pid = ::std::process::id();
+        let proc_handle = vcheat::external::open_proc(pid).unwrap();
+
+        let exe = ::std::env::current_exe().unwrap();
+        let mod_name = exe.file_name().unwrap().to_str().unwrap();
+
+        let mi = vcheat::external::get_mod_info(pid, mod_name).unwrap();
+
+        let path: vcheat::pointer::PointerPath = format!(
+            "\"{}\"+{:X} +4",
+            mod_name,
+            ::core::ptr::addr_of!(POINTER_SLOT) as usize - mi.addr as usize
+        )
+        .parse()
+        .unwrap();
+
+        let addr = path.resolve_remote(proc_handle, pid).unwrap();
+
+        assert_eq!(addr.cast::<u32>(), &POINTER_TARGET[1] as *const u32);
+        assert_eq!(
+            vcheat::external::read::<u32>(proc_handle, addr).unwrap(),
+            0xC0FFEE
+        );
+    }
+}
+
+#[test]
+fn get_pointer_width() {
+    unsafe {
+        let proc_handle = vcheat::external::open_proc(::std::process::id()).unwrap();
+
+        assert_eq!(
+            vcheat::external::get_pointer_width(proc_handle).unwrap(),
+            vcheat::memory::PointerWidth::native()
+        );
+        assert_eq!(
+            vcheat::memory::MemoryAccess::pointer_width(
+                &vcheat::memory::RemoteMemory::new(proc_handle).unwrap()
+            ),
+            vcheat::memory::PointerWidth::native()
+        );
+
+        // A failed detection is an error, not this build's width
+        let gone = u32::MAX as vcheat::HANDLE;
+
+        assert!(vcheat::external::get_pointer_width(gone).is_err());
+        assert!(vcheat::memory::RemoteMemory::new(gone).is_err());
+        assert!(vcheat::external::read_multi_pointer(gone, 0x1000, &[]).is_err());
+        assert_eq!(
+            vcheat::memory::MemoryAccess::pointer_width(
+                &vcheat::memory::RemoteMemory::from_handle(
+                    gone,
+                    vcheat::memory::PointerWidth::Bits32
+                )
+            ),
+            vcheat::memory::PointerWidth::Bits32
+        );
+
+        let mut db = vcheat::sigdb::SigDb::new();
+
+        db.add(vcheat::sigdb::SigEntry {
+            name: "any".to_owned(),
+            module: "game.exe".to_owned(),
+            pattern: vcheat::pattern::Pattern::parse("90").unwrap(),
+            resolver: Default::default(),
+            expected: 1,
+        });
+
+        let report = db.resolve_remote(gone, u32::MAX);
+
+        assert_eq!(report.failed.len(), 1);
+        assert!(report.found.is_empty());
+    }
+}
+
+#[test]
+fn local_memory_checks_access() {
+    use vcheat::memory::MemoryAccess;
+
+    unsafe {
+        let mem = vcheat::memory::LocalMemory::new();
+
+        let mut value: u64 = 0x1122_3344_5566_7788;
+        let addr = ::core::ptr::addr_of_mut!(value) as usize;
+
+        assert_eq!(mem.read::<u64>(addr).unwrap(), 0x1122_3344_5566_7788);
+
+        mem.write(addr, &0xAABBu16).unwrap();
+
+        assert_eq!(::core::ptr::read_volatile(&value) & 0xFFFF, 0xAABB);
+
+        // PROT_NONE, then PROT_READ, MAP_PRIVATE | MAP_ANONYMOUS
+        let none = mmap(::core::ptr::null_mut(), 0x1000, 0, 0x22, -1, 0);
+        let read_only = mmap(::core::ptr::null_mut(), 0x1000, 1, 0x22, -1, 0);
+
+        assert!(mem.read::<u8>(none as usize).is_err());
+        assert_eq!(mem.read::<u8>(read_only as usize).unwrap(), 0);
+        assert!(mem.write(read_only as usize, &1u8).is_err());
+
+        // A chain through wild pointers fails instead of crashing
+        let wild: usize = none as usize;
+
+        assert!(mem
+            .read_multi_pointer(::core::ptr::addr_of!(wild) as usize, &[0x10])
+            .is_err());
+        assert!(mem.read::<u8>(0).is_err());
+
+        munmap(none, 0x1000);
+        munmap(read_only, 0x1000);
+    }
+}
@@ -0,0 +1,378 @@
+use vcheat::memory::{
+    BufferMemory, MemoryAccess, Pod, PointerChainError, PointerWidth, RemotePtr, RemotePtr32,
+};
+
+#[test]
+fn buffer_read_write() {
+    let mem = BufferMemory::new(0x1000, vec![0u8; 0x100]);
+
+    mem.write_bytes(0x1010, &[1, 2, 3, 4]).unwrap();
+
+    assert_eq!(mem.read_vec(0x100F, 6).unwrap(), vec![0, 1, 2, 3, 4, 0]);
+
+    mem.write(0x1020, &0x1122_3344_u32).unwrap();
+
+    assert_eq!(mem.read::<u32>(0x1020).unwrap(), 0x1122_3344);
+    assert_eq!(mem.read::<[u8; 2]>(0x1021).unwrap(), [0x33, 0x22]);
+
+    assert!(mem.read_vec(0xFFF, 2).is_err());
+    assert!(mem.read_vec(0x10FF, 2).is_err());
+    assert!(mem.write_bytes(0x1100, &[0]).is_err());
+
+    assert_eq!(mem.query(0x1010).unwrap().region_size, 0xF0);
+    assert!(mem.query(0x1100).is_err());
+}
+
+#[test]
+fn buffer_multi_pointer() {
+    let mem = BufferMemory::new(0x1000, vec![0u8; 0x100]);
+
+    mem.write_bytes(0x1000, &0x1040_usize.to_ne_bytes())
+        .unwrap();
+    mem.write_bytes(0x1050, &0x1080_usize.to_ne_bytes())
+        .unwrap();
+    mem.write_bytes(0x1088, &0xDEAD_usize.to_ne_bytes())
+        .unwrap();
+
+    assert_eq!(
+        mem.read_multi_pointer(0x1000, &[0x10, 0x8]).unwrap(),
+        0xDEAD
+    );
+    assert!(mem.read_multi_pointer(0x1000, &[0x10, 0x8, 0]).is_err());
+}
+
+#[test]
+fn pointer_chain() {
+    let mem = BufferMemory::new(0x1000, vec![0u8; 0x100]);
+
+    // 32-bit target: 0x1000 -> 0x1040, +0x10 -> 0x1080, +0x8 -> 0xDEAD
+    mem.write(0x1000, &0x1040_u32).unwrap();
+    mem.write(0x1050, &0x1080_u32).unwrap();
+    mem.write(0x1088, &0xDEAD_u32).unwrap();
+    mem.write(0x10F0, &0_u32).unwrap();
+
+    assert_eq!(
+        mem.read_pointer_chain(0x1000, &[0x10, 0x8], PointerWidth::Bits32)
+            .unwrap(),
+        0xDEAD
+    );
+
+    let err = mem
+        .read_pointer_chain(0x1000, &[0x10, 0x8, 0x4], PointerWidth::Bits32)
+        .unwrap_err();
+
+    assert_eq!((err.hop, err.addr), (3, 0xDEB1));
+
+    let err = mem
+        .read_pointer_chain(0x10F0, &[0x10], PointerWidth::Bits32)
+        .unwrap_err();
+
+    assert_eq!((err.hop, err.addr), (1, 0x10));
+
+    let err = mem.read_multi_pointer(0x900, &[]).unwrap_err();
+    let err = err.downcast_ref::<PointerChainError>().unwrap();
+
+    assert_eq!((err.hop, err.addr), (0, 0x900));
+    assert_eq!(
+        PointerWidth::native().size(),
+        ::core::mem::size_of::<usize>()
+    );
+}
+
+#[test]
+fn pointer_width() {
+    let mem = BufferMemory::new(0x1000, vec![0u8; 0x100]).with_pointer_width(PointerWidth::Bits32);
+
+    mem.write(0x1000, &[0x1040_u32, 0xFFFF_FFFF]).unwrap();
+    mem.write(0x1050, &0x1080_u32).unwrap();
+
+    assert_eq!(mem.pointer_width(), PointerWidth::Bits32);
+    assert_eq!(mem.read_ptr(0x1000).unwrap(), 0x1040);
+    assert_eq!(mem.read_multi_pointer(0x1000, &[0x10]).unwrap(), 0x1080);
+    assert_eq!(
+        RemotePtr::<u32>::new(0x1000)
+            .read_ptr::<u32, _>(&mem)
+            .unwrap()
+            .addr(),
+        0x1040
+    );
+
+    // Wraps at 32 bits: 0xFFFFFFFF + 0x1051 = 0x1050
+    assert_eq!(mem.read_multi_pointer(0x1004, &[0x1051]).unwrap(), 0x1080);
+
+    let cached = vcheat::memory::CachedMemory::new(mem.clone());
+
+    assert_eq!(cached.pointer_width(), PointerWidth::Bits32);
+    assert_eq!(cached.read_multi_pointer(0x1000, &[0x10]).unwrap(), 0x1080);
+}
+
+#[test]
+fn remote_ptr() {
+    let mem = BufferMemory::new(0x1000, vec![0u8; 0x100]);
+
+    let table = RemotePtr::<u32>::new(0x1040);
+
+    assert_eq!(table.offset(2).addr(), 0x1048);
+    assert_eq!(table.offset(-1).byte_offset(2).addr(), 0x103E);
+    assert_eq!(table.cast::<u64>().offset(1).addr(), 0x1048);
+    assert_eq!(table.to_string(), "0x1040");
+
+    table.offset(1).write(&mem, &0xCAFE).unwrap();
+
+    assert_eq!(table.offset(1).read(&mem).unwrap(), 0xCAFE);
+    assert_eq!(table.read_slice(&mem, 2).unwrap(), vec![0, 0xCAFE]);
+
+    // A pointer to `table`, stored as a Pod field
+    RemotePtr::<RemotePtr<u32>>::new(0x1000)
+        .write(&mem, &table)
+        .unwrap();
+
+    let followed = RemotePtr::<usize>::new(0x1000)
+        .read_ptr::<u32, _>(&mem)
+        .unwrap();
+
+    assert_eq!(followed, table);
+    assert_eq!(followed.offset(1).read(&mem).unwrap(), 0xCAFE);
+
+    assert!(RemotePtr::<u32>::null().is_null());
+    assert!(RemotePtr::<u32>::null().read(&mem).is_err());
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Entity32 {
+    name: RemotePtr32<u8>,
+    health: u32,
+}
+
+unsafe impl Pod for Entity32 {}
+
+#[test]
+fn remote_ptr_32() {
+    let mem = BufferMemory::new(0x1000, vec![0u8; 0x100]).with_pointer_width(PointerWidth::Bits32);
+
+    assert_eq!(::core::mem::size_of::<Entity32>(), 8);
+
+    mem.write(0x1000, &[0x1040_u32, 100]).unwrap();
+    mem.write_bytes(0x1040, b"P").unwrap();
+
+    let entity = mem.read::<Entity32>(0x1000).unwrap();
+
+    assert_eq!(entity.health, 100);
+    assert_eq!(entity.name, RemotePtr32::new(0x1040));
+    assert_eq!(entity.name.read(&mem).unwrap(), b'P');
+    assert_eq!(RemotePtr::from(entity.name), RemotePtr::<u8>::new(0x1040));
+
+    // Same address as the width-aware path
+    assert_eq!(
+        RemotePtr::<RemotePtr32<u8>>::new(0x1000)
+            .read_ptr::<u8, _>(&mem)
+            .unwrap(),
+        entity.name.get()
+    );
+
+    assert_eq!(
+        RemotePtr32::try_from(RemotePtr::<u8>::new(0x1040)),
+        Ok(entity.name)
+    );
+
+    #[cfg(target_pointer_width = "64")]
+    assert!(RemotePtr32::try_from(RemotePtr::<u8>::new(0x1_0000_0000)).is_err());
+    assert!(RemotePtr32::<u8>::null().read(&mem).is_err());
+}
+
+#[test]
+fn scan_mem() {
+    // Straddles the 1 MiB chunk boundary
+    let mut data = vec![0u8; 0x20_0000];
+
+    data[0x10_0000 - 2..0x10_0000 + 2].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+    data[0x10..0x14].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+
+    let mem = BufferMemory::new(0x40_0000, data);
+
+    let pat = vcheat::pattern::Pattern::parse("DE AD ?? EF").unwrap();
+
+    assert_eq!(
+        pat.scan_mem(&mem, 0x40_0000, mem.len()).unwrap(),
+        vec![0x40_0010, 0x40_0000 + 0x10_0000 - 2]
+    );
+
+    let pat = vcheat::pattern::ExtPattern::parse("DE [1-2] EF").unwrap();
+
+    assert_eq!(
+        pat.scan_mem(&mem, 0x40_0000, mem.len()).unwrap(),
+        vec![0x40_0010, 0x40_0000 + 0x10_0000 - 2]
+    );
+
+    assert!(pat.scan_mem(&mem, 0x40_0000, mem.len() + 1).is_err());
+}
+
+#[test]
+fn resolve_mem() {
+    // 0x1000: mov rax, [rip+0x10]  ; 48 8B 05 10 00 00 00
+    let mut data = vec![0u8; 0x40];
+
+    data[..7].copy_from_slice(&[0x48, 0x8B, 0x05, 0x10, 0x00, 0x00, 0x00]);
+    data[0x17..0x1F].copy_from_slice(&0x1030_usize.to_le_bytes());
+
+    let mem = BufferMemory::new(0x1000, data);
+
+    let resolver = vcheat::resolver::Resolver::new().rip_relative(3, 7).deref();
+
+    assert_eq!(resolver.resolve_mem(&mem, 0x1000).unwrap(), 0x1030);
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Vec3 {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+unsafe impl Pod for Vec3 {}
+
+#[test]
+fn read_write_pod() {
+    let mem = BufferMemory::new(0x1000, vec![0u8; 0x40]);
+
+    let v = Vec3 {
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+    };
+
+    mem.write(0x1000, &v).unwrap();
+    mem.write(0x100C, &[4.0f32, 5.0, 6.0]).unwrap();
+
+    assert_eq!(mem.read::<Vec3>(0x1000).unwrap(), v);
+    assert_eq!(
+        mem.read_slice::<Vec3>(0x1000, 2).unwrap()[1],
+        Vec3 {
+            x: 4.0,
+            y: 5.0,
+            z: 6.0
+        }
+    );
+
+    // Never half-initialized
+    assert!(mem.read::<Vec3>(0x1038).is_err());
+    assert!(mem.read_slice::<u32>(0x1000, 0x11).is_err());
+    assert!(mem.read_slice::<u64>(0x1000, usize::MAX).is_err());
+    assert!(mem.read_slice::<u32>(0x1000, 0).unwrap().is_empty());
+}
+
+#[test]
+fn read_write_strings() {
+    // Two pages, everything past 0x3000 is unreadable
+    let mem = BufferMemory::new(0x1000, vec![0xFFu8; 0x2000]);
+
+    mem.write_c_str(0x2FFC, "abc", 4).unwrap();
+
+    assert_eq!(mem.read_c_str(0x2FFC, 0x100).unwrap(), "abc");
+    assert_eq!(mem.read_c_str(0x2FFC, 2).unwrap(), "ab");
+    assert_eq!(mem.read_str(0x2FFC, 3).unwrap(), "abc");
+
+    assert!(mem.write_c_str(0x2000, "abcd", 4).is_err());
+    assert!(mem.write_c_str(0x2000, "a\0b", 8).is_err());
+
+    // Unterminated until the unreadable page
+    let unterminated = BufferMemory::new(0x1000, vec![b'x'; 0x1000]);
+
+    assert!(unterminated.read_c_str(0x1FF0, 0x100).is_err());
+    assert_eq!(unterminated.read_c_str(0x1FF0, 0x10).unwrap().len(), 0x10);
+
+    // Odd address, crossing the page boundary
+    mem.write_c_str_utf16(0x1FFB, "Wide ü", 7).unwrap();
+
+    assert_eq!(mem.read_c_str_utf16(0x1FFB, 0x100).unwrap(), "Wide ü");
+    assert_eq!(mem.read_c_str_utf16(0x1FFB, 4).unwrap(), "Wide");
+    assert_eq!(mem.read_str_utf16(0x1FFB, 2).unwrap(), "Wi");
+
+    assert!(mem.write_c_str_utf16(0x1FFB, "Wide ü", 6).is_err());
+}
+
+#[test]
+fn read_batch() {
+    let mem = BufferMemory::new(0x1000, (0..=0xFFu8).collect());
+
+    let mut a = [0u8; 2];
+    let mut b = [0u8; 4];
+    let mut c = [0u8; 1];
+
+    let results = mem.read_batch(&mut [
+        vcheat::memory::ReadRequest::new(0x1010, &mut a),
+        vcheat::memory::ReadRequest::new(0x10FE, &mut b),
+        vcheat::memory::ReadRequest::new(0x10FF, &mut c),
+    ]);
+
+    assert!(results[0].is_ok() && results[1].is_err() && results[2].is_ok());
+    assert_eq!((a, c), ([0x10, 0x11], [0xFF]));
+}
+
+#[test]
+fn cached_memory() {
+    let cached = vcheat::memory::CachedMemory::new(BufferMemory::new(0x1000, vec![0u8; 0x3000]));
+
+    // Crosses into the second page
+    assert_eq!(cached.read_vec(0x1FFE, 4).unwrap(), vec![0; 4]);
+    assert_eq!(cached.read::<u32>(0x1FFE).unwrap(), 0);
+    assert_eq!((cached.stats().hits, cached.stats().misses), (2, 2));
+
+    // Changed behind the cache's back
+    cached.inner().write_bytes(0x1FFE, &[1, 2, 3, 4]).unwrap();
+
+    assert_eq!(cached.read_vec(0x1FFE, 4).unwrap(), vec![0; 4]);
+
+    cached.invalidate(0x2000, 1);
+
+    assert_eq!(cached.read_vec(0x1FFE, 4).unwrap(), vec![0, 0, 3, 4]);
+
+    cached.next_generation();
+
+    assert_eq!(cached.read_vec(0x1FFE, 4).unwrap(), vec![1, 2, 3, 4]);
+
+    // Written through
+    cached.write(0x1FFF, &0xAABBu16).unwrap();
+
+    assert_eq!(
+        cached.inner().read_vec(0x1FFE, 4).unwrap(),
+        vec![1, 0xBB, 0xAA, 4]
+    );
+
+    cached.reset_stats();
+
+    assert_eq!(cached.read_vec(0x1FFE, 4).unwrap(), vec![1, 0xBB, 0xAA, 4]);
+    assert_eq!(cached.stats().hit_rate(), 1.0);
+
+    cached.invalidate_all();
+
+    assert!(cached.read_vec(0x3FFF, 2).is_err());
+    assert_eq!(cached.stats().misses, 2);
+
+    // Stale pages don't pile up, 0x4000 is outside of the buffer
+    assert_eq!(cached.cached_pages(), 1);
+
+    cached.next_generation();
+
+    assert_eq!(cached.cached_pages(), 0);
+
+    let capped = vcheat::memory::CachedMemory::new(BufferMemory::new(0x1000, vec![0u8; 0x3000]))
+        .max_pages(2);
+
+    capped.read_vec(0x1000, 0x3000).unwrap();
+
+    assert_eq!(capped.cached_pages(), 2);
+    assert_eq!(capped.read::<u8>(0x3000).unwrap(), 0);
+    assert_eq!(capped.stats().hits, 1);
+
+    // Never valid
+    let uncached = vcheat::memory::CachedMemory::new(BufferMemory::new(0x1000, vec![0u8; 0x80]))
+        .ttl(::std::time::Duration::ZERO);
+
+    // The page is only partly backed, so it is read directly
+    assert_eq!(uncached.read_vec(0x1010, 4).unwrap(), vec![0; 4]);
+    assert_eq!(uncached.read_vec(0x1010, 4).unwrap(), vec![0; 4]);
+    assert_eq!(uncached.stats().hits, 0);
+}
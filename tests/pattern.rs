@@ -0,0 +1,367 @@
+#[test]
+fn literal_zero_is_not_wildcard() {
+    let data = [0x48, 0x11, 0x22, 0x8B, 0x48, 0x00, 0x00, 0x8B];
+
+    let pat = vcheat::pattern::Pattern::parse("48 00 00 8B").unwrap();
+
+    assert_eq!(pat.scan(&data), vec![4]);
+
+    unsafe {
+        assert_eq!(vcheat::pat_find("48 00 00 8B", &data).unwrap(), 4);
+        assert_eq!(vcheat::pat_scan("48 ?? ?? 8B", &data).unwrap(), vec![0, 4]);
+    }
+}
+
+#[test]
+fn rejects_signed_bytes() {
+    for pat in ["48 +A 8B", "+A", "48 -1", "48 +F?"] {
+        assert!(vcheat::pattern::Pattern::parse(pat).is_err(), "{}", pat);
+        assert!(vcheat::pattern::ExtPattern::parse(pat).is_err(), "{}", pat);
+    }
+
+    assert_eq!(
+        vcheat::pattern::Pattern::parse("48 A").unwrap().to_string(),
+        "48 0A"
+    );
+}
+
+#[test]
+fn matches_at_out_of_range() {
+    let data = [0x48, 0x8B, 0x05];
+
+    let pat = vcheat::pattern::Pattern::parse("48 8B").unwrap();
+
+    assert!(pat.matches_at(&data, 0));
+    assert!(!pat.matches_at(&data, 2));
+    assert!(!pat.matches_at(&data, usize::MAX));
+
+    let pat = vcheat::pattern::ExtPattern::parse("48 [0-2] 05").unwrap();
+
+    assert!(pat.matches_at(&data, 0));
+    assert!(!pat.matches_at(&data, usize::MAX));
+}
+
+#[test]
+fn display_round_trip() {
+    let pat = vcheat::pattern::Pattern::parse("48 8b * ? 00 ff").unwrap();
+
+    assert_eq!(pat.to_string(), "48 8B ?? ?? 00 FF");
+
+    assert_eq!(
+        vcheat::pattern::Pattern::parse(pat.to_string()).unwrap(),
+        pat
+    );
+}
+
+#[test]
+fn scan_overlapping() {
+    let data = [0xAA; 6];
+
+    let pat = vcheat::pattern::Pattern::parse("AA ?? AA").unwrap();
+
+    assert_eq!(pat.find(&data), Some(0));
+    assert_eq!(pat.scan(&data), vec![0, 1, 2, 3]);
+    assert!(vcheat::pattern::Pattern::parse("").is_err());
+}
+
+#[test]
+fn nibble_wildcards() {
+    let data = [0x48, 0x8B, 0xC8, 0x4C, 0x8B, 0xC1, 0x49, 0x8B, 0xD0];
+
+    let pat = vcheat::pattern::Pattern::parse("4? 8B C?").unwrap();
+
+    assert_eq!(pat.to_string(), "4? 8B C?");
+    assert_eq!(pat.scan(&data), vec![0, 3]);
+
+    let pat = vcheat::pattern::Pattern::parse("?9 8B ?0").unwrap();
+
+    assert_eq!(pat.find(&data), Some(6));
+
+    unsafe {
+        assert_eq!(vcheat::pat_scan("4? 8B", &data).unwrap(), vec![0, 3, 6]);
+    }
+
+    assert!(vcheat::pattern::Pattern::parse("4?? 8B").is_err());
+}
+
+#[test]
+fn simd_matches_scalar() {
+    let mut state: u32 = 0x1234_5678;
+
+    let mut data: Vec<u8> = (0..0x10000)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state % 4) as u8 * 0x11
+        })
+        .collect();
+
+    data[0xFFFC..].copy_from_slice(&[0x00, 0x11, 0x22, 0x33]);
+
+    for pat in [
+        "00 11",
+        "00 ?? 22",
+        "?? 33 ?? ?? 00",
+        "1? 22 ?3",
+        "?? ??",
+        "11 22 33 00 11 22 33 00 11 22 33 00 11 22 33 00 11 22 33 00 11 22 33 00 11 22 33 00 11 22 33 00 11",
+    ] {
+        let pat = vcheat::pattern::Pattern::parse(pat).unwrap();
+
+        assert_eq!(pat.scan(&data), pat.scan_scalar(&data), "{}", pat);
+        assert_eq!(pat.find(&data), pat.find_scalar(&data), "{}", pat);
+    }
+}
+
+#[test]
+fn pattern_set_single_pass() {
+    let data = [
+        0x48, 0x8B, 0x05, 0x10, 0x20, 0x30, 0x40, 0xE8, 0x01, 0x02, 0x03, 0x04, 0x48, 0x8B, 0x05,
+        0xAA, 0xBB, 0xCC, 0xDD, 0x90, 0x90,
+    ];
+
+    let set: vcheat::pattern::PatternSet = [
+        ("global", "48 8B 05 ?? ?? ?? ??"),
+        ("call", "E8 ?? ?? ?? ?? 48"),
+        ("nop", "90"),
+        ("any_rex", "4? 8B"),
+        ("missing", "CC CC CC"),
+        (
+            "wild",
+            "?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ??",
+        ),
+    ]
+    .into_iter()
+    .map(|(name, pat)| (name, vcheat::pattern::Pattern::parse(pat).unwrap()))
+    .collect();
+
+    let result = set.scan(&data);
+
+    assert_eq!(result.len(), set.len());
+
+    for (name, pat) in set.iter() {
+        assert_eq!(result[name], pat.scan(&data), "{}", name);
+    }
+
+    assert_eq!(result["global"], vec![0, 12]);
+    assert!(result["missing"].is_empty());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn par_scan_matches_scan() {
+    let data: Vec<u8> = (0..0x4000u32).map(|i| (i * 7 % 13) as u8).collect();
+
+    for pat in ["00 07 0E", "?? 01 ?? 0?", "0C"] {
+        let pat = vcheat::pattern::Pattern::parse(pat).unwrap();
+
+        let expected = pat.scan(&data);
+
+        assert_eq!(pat.par_scan(&data), expected, "{}", pat);
+
+        for chunk_size in [1, 2, 3, 13, 100, 0x1000] {
+            assert_eq!(
+                pat.par_scan_chunked(&data, chunk_size),
+                expected,
+                "{} / {}",
+                pat,
+                chunk_size
+            );
+        }
+    }
+}
+
+#[test]
+fn signature_formats() {
+    let ida = vcheat::pattern::Pattern::parse("48 8B 05 ? ? ? ? 00").unwrap();
+
+    let code =
+        vcheat::pattern::Pattern::parse_code(r"\x48\x8B\x05\xAA\xBB\xCC\xDD\x00", "xxx????x")
+            .unwrap();
+
+    let code_bytes =
+        vcheat::pattern::Pattern::from_code(b"\x48\x8B\x05\x00\x00\x00\x00\x00", "xxx????x")
+            .unwrap();
+
+    let x64dbg = vcheat::pattern::Pattern::parse_x64dbg("488B05 ????????00").unwrap();
+
+    assert_eq!(ida, code);
+    assert_eq!(ida, code_bytes);
+    assert_eq!(ida, x64dbg);
+
+    assert_eq!(ida.to_ida(), "48 8B 05 ? ? ? ? 00");
+    assert_eq!(ida.to_x64dbg(), "48 8B 05 ?? ?? ?? ?? 00");
+
+    let (bytes, mask) = ida.to_code().unwrap();
+
+    assert_eq!(bytes, r"\x48\x8B\x05\x00\x00\x00\x00\x00");
+    assert_eq!(mask, "xxx????x");
+    assert_eq!(
+        vcheat::pattern::Pattern::parse_code(bytes, mask).unwrap(),
+        ida
+    );
+
+    assert!(vcheat::pattern::Pattern::parse("4? 8B")
+        .unwrap()
+        .to_code()
+        .is_err());
+    assert!(vcheat::pattern::Pattern::parse_code(r"\x48\x8B", "x").is_err());
+    assert!(vcheat::pattern::Pattern::parse_x64dbg("488").is_err());
+}
+
+#[test]
+fn generate_unique() {
+    let func = [
+        0x48, 0x89, 0x5C, 0x24, 0x08, // mov [rsp+8], rbx
+        0x48, 0x8B, 0x05, 0x11, 0x22, 0x33, 0x44, // mov rax, [rip+disp32]
+        0xE8, 0x55, 0x66, 0x77, 0x08, // call rel32
+        0x85, 0xC0, // test eax, eax
+        0x74, 0x05, // je +5
+        0xC3, // ret
+    ];
+
+    let mut data = Vec::new();
+
+    data.extend_from_slice(&func[..12]);
+    data.extend_from_slice(&[0xCC; 4]);
+    data.extend_from_slice(&func);
+    data.extend_from_slice(&[0xCC; 4]);
+
+    let pat = vcheat::pattern::Pattern::generate(&data, 16).unwrap();
+
+    assert_eq!(pat.to_string(), "48 89 5C 24 08 48 8B 05 ?? ?? ?? ?? E8");
+    assert_eq!(pat.scan(&data), vec![16]);
+
+    let pat = vcheat::pattern::Pattern::generate(&data, 16 + 17).unwrap();
+
+    assert_eq!(pat.scan(&data), vec![16 + 17]);
+
+    assert!(vcheat::pattern::Pattern::generate(&data, data.len()).is_err());
+}
+
+#[test]
+fn extended_syntax() {
+    let data = [
+        0xE8, 0x01, 0x02, 0x03, 0x04, 0x90, 0x48, 0x8B, // call, nop, mov
+        0x74, 0x02, 0x75, 0x10, 0x7F, 0x00, 0xEB, 0x00, // je, jne, jg, jmp
+    ];
+
+    let pat = vcheat::pattern::ExtPattern::parse("E8 [4-16] 48 8B").unwrap();
+
+    assert!(pat.as_plain().is_none());
+    assert_eq!(pat.scan(&data), vec![0]);
+    assert!(vcheat::pattern::ExtPattern::parse("E8 [6-16] 48 8B")
+        .unwrap()
+        .find(&data)
+        .is_none());
+
+    let pat = vcheat::pattern::ExtPattern::parse("(74|75) !00").unwrap();
+
+    assert_eq!(pat.scan(&data), vec![8, 10]);
+    assert_eq!(pat.to_string(), "(74-75) !00");
+
+    let pat = vcheat::pattern::ExtPattern::parse("[70-7F] ??").unwrap();
+
+    assert_eq!(pat.scan(&data), vec![8, 10, 12]);
+    assert_eq!(pat.to_string(), "(70-7F) ??");
+    assert_eq!(
+        vcheat::pattern::ExtPattern::parse("(70-7F) ??").unwrap(),
+        pat
+    );
+
+    // Two hex digits on both sides make a byte range, a leading zero makes a gap
+    let pat = vcheat::pattern::ExtPattern::parse("E8 [10-16] 48").unwrap();
+
+    assert_eq!(pat.len_range(), (3, 3));
+
+    let pat = vcheat::pattern::ExtPattern::parse("E8 [010-016] 48").unwrap();
+
+    assert_eq!(pat.len_range(), (12, 18));
+    assert_eq!(pat.to_string(), "E8 [010-016] 48");
+
+    for text in [
+        "E8 [010-20] ?? (70-7F|E9) 4?",
+        "[8] !4? [3-4] (00|0F)",
+        "[0A-0F] [16] [4-100]",
+    ] {
+        let pat = vcheat::pattern::ExtPattern::parse(text).unwrap();
+
+        assert_eq!(
+            vcheat::pattern::ExtPattern::parse(pat.to_string()).unwrap(),
+            pat
+        );
+    }
+
+    let pat = vcheat::pattern::ExtPattern::parse("48 8B").unwrap();
+
+    assert!(pat.as_plain().is_some());
+    assert_eq!(pat.scan(&data), vec![6]);
+
+    unsafe {
+        assert_eq!(vcheat::pat_find("(75|EB) 00", &data).unwrap(), 14);
+        assert_eq!(vcheat::pat_scan("E8 [1] 02", &data).unwrap(), vec![0]);
+    }
+
+    assert!(vcheat::pattern::ExtPattern::parse("[7F-70]").is_err());
+    assert!(vcheat::pattern::ExtPattern::parse("[7?-7F]").is_err());
+    assert!(vcheat::pattern::ExtPattern::parse("[70-7FF]").is_err());
+    assert!(vcheat::pattern::ExtPattern::parse("[+4]").is_err());
+    assert!(vcheat::pattern::ExtPattern::parse("(+7-7F)").is_err());
+    assert!(vcheat::pattern::ExtPattern::parse("[16-4]").is_err());
+    assert!(vcheat::pattern::ExtPattern::parse("[4]").is_err());
+    assert!(vcheat::pattern::ExtPattern::parse("!??").is_err());
+}
+
+#[test]
+fn many_gaps_stay_polynomial() {
+    let data = vec![0u8; 0x800];
+
+    let pat = vcheat::pattern::ExtPattern::parse("00 [0-1000] 00 [0-1000] FF").unwrap();
+
+    let start = ::std::time::Instant::now();
+
+    assert!(pat.scan(&data).is_empty());
+    assert!(start.elapsed() < ::std::time::Duration::from_secs(5));
+
+    let mut data = data;
+
+    data[0x7FF] = 0xFF;
+
+    // The longest match spans 2003 bytes
+    assert_eq!(pat.find(&data), Some(0x7FF - 2002));
+    assert_eq!(pat.scan(&data).len(), 2001);
+}
+
+#[test]
+fn lazy_matches() {
+    let data = [0xAA, 0xAA, 0xAA, 0xAA, 0xBB, 0xAA, 0xAA];
+
+    unsafe {
+        let iter = vcheat::pat_scan_iter("AA AA", &data).unwrap();
+
+        assert_eq!(iter.clone().collect::<Vec<usize>>(), vec![0, 1, 2, 5]);
+        assert_eq!(
+            iter.clone().overlapping(false).collect::<Vec<usize>>(),
+            vec![0, 2, 5]
+        );
+        assert_eq!(iter.clone().start(1).nth(2), Some(5));
+        assert_eq!(
+            iter.clone().start(1).end(4).collect::<Vec<usize>>(),
+            vec![1, 2]
+        );
+        assert_eq!(iter.start(7).next(), None);
+
+        assert_eq!(
+            vcheat::pat_scan_iter("AA [0-2] BB", &data)
+                .unwrap()
+                .overlapping(false)
+                .collect::<Vec<usize>>(),
+            vec![1]
+        );
+    }
+
+    let pat = vcheat::pattern::Pattern::parse("AA").unwrap();
+
+    assert_eq!(pat.matches(&data).count(), pat.scan(&data).len());
+}
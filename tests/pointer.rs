@@ -0,0 +1,78 @@
+use vcheat::memory::{BufferMemory, MemoryAccess};
+use vcheat::pointer::PointerPath;
+
+#[test]
+fn parse_format() {
+    let path = PointerPath::parse("\"game.exe\"+1A2B3C -> 10 -> 28 +8").unwrap();
+
+    assert_eq!(
+        path,
+        PointerPath {
+            module: Some("game.exe".to_owned()),
+            base: 0x1A2B3C,
+            offsets: vec![0x10, 0x28],
+            last_offset: 8,
+        }
+    );
+    assert_eq!(path.to_string(), "\"game.exe\"+1A2B3C -> 10 -> 28 +8");
+
+    assert_eq!(
+        "game.exe+0x1a2b3c->10->-0x28-8"
+            .parse::<PointerPath>()
+            .unwrap()
+            .to_string(),
+        "\"game.exe\"+1A2B3C -> 10 -> -28 -8"
+    );
+    assert_eq!(
+        PointerPath::parse("\"My Game.exe\"")
+            .unwrap()
+            .module
+            .as_deref(),
+        Some("My Game.exe")
+    );
+
+    let absolute = PointerPath::parse("7FF61A2B3C -> 8").unwrap();
+
+    assert_eq!((absolute.module, absolute.base), (None, 0x7FF61A2B3C));
+
+    for bad in [
+        "",
+        "\"game.exe+10",
+        "\"game.exe\"+ -> 10",
+        "\"game.exe\"+10 -> 10 8",
+        "\"game.exe\"+10 -> 10 +8 +8",
+        "\"game.exe\"+10 -> xyz",
+        "-> 10",
+    ] {
+        assert!(PointerPath::parse(bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn resolve_mem() {
+    let mem = BufferMemory::new(0x1000, vec![0u8; 0x100]);
+
+    mem.write(0x1010, &0x1040_usize).unwrap();
+    mem.write(0x1050, &0x1080_usize).unwrap();
+
+    let path = PointerPath::parse("\"game.exe\"+10 -> 10 +8").unwrap();
+
+    assert_eq!(path.resolve_mem(&mem, 0x1000).unwrap(), 0x1088);
+
+    assert!(PointerPath::parse("\"game.exe\"+10 -> 10 -> 80")
+        .unwrap()
+        .resolve_mem(&mem, 0x1000)
+        .is_err());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn serde_string() {
+    let path = PointerPath::parse("\"game.exe\"+1A2B3C -> 10 -> 28 +8").unwrap();
+
+    let json = serde_json::to_string(&path).unwrap();
+
+    assert_eq!(json, r#""\"game.exe\"+1A2B3C -> 10 -> 28 +8""#);
+    assert_eq!(serde_json::from_str::<PointerPath>(&json).unwrap(), path);
+    assert!(serde_json::from_str::<PointerPath>(r#""-> 10""#).is_err());
+}
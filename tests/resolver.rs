@@ -0,0 +1,98 @@
+const NATIVE: vcheat::memory::PointerWidth = vcheat::memory::PointerWidth::native();
+
+#[test]
+fn resolve_local() {
+    // 0x1000: mov rax, [rip+0x10]  ; 48 8B 05 10 00 00 00
+    // 0x1007: call 0x1020          ; E8 14 00 00 00
+    // 0x100C: jmp 0x1000           ; EB F2
+    let mut data = vec![0u8; 0x40];
+
+    data[..0xE].copy_from_slice(&[
+        0x48, 0x8B, 0x05, 0x10, 0x00, 0x00, 0x00, 0xE8, 0x14, 0x00, 0x00, 0x00, 0xEB, 0xF2,
+    ]);
+
+    data[0x17..0x1F].copy_from_slice(&0x1030_usize.to_le_bytes());
+
+    let base = 0x1000;
+
+    let offset = vcheat::pattern::Pattern::parse("48 8B 05 ?? ?? ?? ?? E8")
+        .unwrap()
+        .find(&data)
+        .unwrap();
+
+    let resolver = vcheat::resolver::Resolver::new().rip_relative(3, 7);
+
+    assert_eq!(
+        resolver.resolve_local(&data, base, offset, NATIVE).unwrap(),
+        0x1017
+    );
+
+    assert_eq!(
+        resolver
+            .deref()
+            .resolve_local(&data, base, offset, NATIVE)
+            .unwrap(),
+        0x1030
+    );
+
+    let resolver = vcheat::resolver::Resolver::new().add(7).follow_call();
+
+    assert_eq!(
+        resolver.resolve_local(&data, base, offset, NATIVE).unwrap(),
+        0x1020
+    );
+
+    let resolver = vcheat::resolver::Resolver::new().add(0xC).follow_jmp();
+
+    assert_eq!(
+        resolver.resolve_local(&data, base, offset, NATIVE).unwrap(),
+        0x1000
+    );
+
+    assert!(vcheat::resolver::Resolver::new()
+        .follow_call()
+        .resolve_local(&data, base, offset, NATIVE)
+        .is_err());
+
+    assert!(vcheat::resolver::Resolver::new()
+        .add(0x40)
+        .deref()
+        .resolve_local(&data, base, offset, NATIVE)
+        .is_err());
+}
+
+#[test]
+fn deref_32_bit() {
+    use vcheat::memory::{BufferMemory, MemoryAccess, PointerWidth};
+
+    // A 32-bit pointer followed by garbage
+    let mut data = vec![0xAAu8; 0x20];
+
+    data[0x10..0x14].copy_from_slice(&0x1010u32.to_le_bytes());
+
+    let resolver = vcheat::resolver::Resolver::new().add(0x10).deref();
+
+    assert_eq!(
+        resolver
+            .resolve_local(&data, 0x1000, 0, PointerWidth::Bits32)
+            .unwrap(),
+        0x1010
+    );
+
+    let mem = BufferMemory::new(0x1000, data).with_pointer_width(PointerWidth::Bits32);
+
+    assert_eq!(resolver.resolve_mem(&mem, 0x1000).unwrap(), 0x1010);
+    assert_eq!(
+        resolver.resolve_mem(&mem, 0x1000).unwrap(),
+        mem.read_ptr(0x1010).unwrap()
+    );
+
+    // Wraps at 32 bits
+    assert_eq!(
+        vcheat::resolver::Resolver::new()
+            .add(-0x2000)
+            .resolve_mem(&mem, 0x1000)
+            .unwrap(),
+        0xFFFF_F000
+    );
+}
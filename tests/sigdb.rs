@@ -0,0 +1,102 @@
+fn module() -> Vec<u8> {
+    let mut data = vec![0xCC; 0x40];
+
+    // 0x10: mov rax, [rip+0x10] ; test rax, rax
+    data[0x10..0x1A].copy_from_slice(&[0x48, 0x8B, 0x05, 0x10, 0x00, 0x00, 0x00, 0x48, 0x85, 0xC0]);
+    data[0x20..0x22].copy_from_slice(&[0x90, 0x90]);
+    data[0x30..0x32].copy_from_slice(&[0x90, 0x90]);
+
+    data
+}
+
+fn entry(
+    name: &str,
+    pattern: &str,
+    resolver: vcheat::resolver::Resolver,
+) -> vcheat::sigdb::SigEntry {
+    vcheat::sigdb::SigEntry {
+        name: name.to_owned(),
+        module: "game.exe".to_owned(),
+        pattern: vcheat::pattern::Pattern::parse(pattern).unwrap(),
+        resolver,
+        expected: 1,
+    }
+}
+
+#[test]
+fn resolve_buffer() {
+    let mut db = vcheat::sigdb::SigDb::new();
+
+    db.add(entry(
+        "global",
+        "48 8B 05 ?? ?? ?? ?? 48 85 C0",
+        vcheat::resolver::Resolver::new().rip_relative(3, 7),
+    ))
+    .add(entry("nops", "90 90", Default::default()))
+    .add(entry("missing", "E8 ?? ?? ?? ??", Default::default()))
+    .add(entry(
+        "broken",
+        "48 85 C0",
+        vcheat::resolver::Resolver::new().add(0x100).deref(),
+    ));
+
+    let report = db.resolve_buffer(
+        "GAME.EXE",
+        &module(),
+        0x140000000,
+        vcheat::memory::PointerWidth::native(),
+    );
+
+    assert!(!report.is_ok());
+    assert_eq!(report.get("global"), Some(0x140000027));
+    assert_eq!(report.not_found, vec!["missing".to_owned()]);
+    assert_eq!(report.ambiguous.len(), 1);
+    assert_eq!(report.ambiguous[0].offsets, vec![0x20, 0x30]);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].name, "broken");
+
+    assert!(db
+        .resolve_buffer(
+            "other.dll",
+            &module(),
+            0,
+            vcheat::memory::PointerWidth::native()
+        )
+        .found
+        .is_empty());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn load_json() {
+    let db = vcheat::sigdb::SigDb::from_json(
+        r#"{
+            "signatures": [
+                {
+                    "name": "global",
+                    "module": "game.exe",
+                    "pattern": "48 8B 05 ? ? ? ? 4? 85 C0",
+                    "steps": [{ "rip_relative": { "disp_offset": 3, "insn_len": 7 } }, { "add": -8 }]
+                }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(db.signatures[0].expected, 1);
+
+    let report = db.resolve_buffer(
+        "game.exe",
+        &module(),
+        0x1000,
+        vcheat::memory::PointerWidth::native(),
+    );
+
+    assert!(report.is_ok());
+    assert_eq!(report.get("global"), Some(0x101F));
+
+    assert_eq!(
+        vcheat::sigdb::SigDb::from_json(db.to_json().unwrap()).unwrap(),
+        db
+    );
+}
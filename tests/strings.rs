@@ -0,0 +1,119 @@
+use vcheat::strings::{RefKind, StrEncoding};
+
+#[test]
+fn find_strings() {
+    let mut data = b"\x00\x01abc\x00Player health\x00\x00\x00".to_vec();
+
+    data.extend("Wide".encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+    data.extend([0, 0]);
+
+    let found = vcheat::strings::find_strings(&data, 4);
+
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].offset, 6);
+    assert_eq!(found[0].encoding, StrEncoding::Ascii);
+    assert_eq!(found[0].text, "Player health");
+    assert_eq!(found[1].offset, 22);
+    assert_eq!(found[1].encoding, StrEncoding::Utf16Le);
+    assert_eq!(found[1].text, "Wide");
+
+    assert_eq!(
+        vcheat::strings::find_str(&data, "health", StrEncoding::Ascii),
+        vec![13]
+    );
+    assert!(vcheat::strings::find_str(&data, "Player", StrEncoding::Ascii).is_empty());
+    assert_eq!(
+        vcheat::strings::find_str(&data, "Wide", StrEncoding::Utf16Le),
+        vec![22]
+    );
+}
+
+#[test]
+fn find_str_refs() {
+    // 0x1000: lea rcx, [rip+0x39]  ; 48 8D 0D 39 00 00 00
+    // 0x1007: mov eax, [rip+0x32]  ; 8B 05 33 00 00 00
+    // 0x1020: dq 0x1040
+    // 0x1040: "Player health"
+    let mut data = vec![0u8; 0x60];
+
+    data[..0xD].copy_from_slice(&[
+        0x48, 0x8D, 0x0D, 0x39, 0x00, 0x00, 0x00, 0x8B, 0x05, 0x33, 0x00, 0x00, 0x00,
+    ]);
+
+    data[0x20..0x28].copy_from_slice(&0x1040_usize.to_le_bytes());
+    data[0x40..0x4D].copy_from_slice(b"Player health");
+
+    let base = 0x1000;
+
+    let result = vcheat::strings::find_str_refs(
+        &data,
+        base,
+        "Player health",
+        vcheat::memory::PointerWidth::Bits64,
+    );
+
+    assert_eq!(result.len(), 1);
+
+    let (found, refs) = &result[0];
+
+    assert_eq!(found.offset, 0x40);
+    assert_eq!(
+        refs.iter()
+            .map(|r| (r.addr, r.kind))
+            .collect::<Vec<(usize, RefKind)>>(),
+        vec![
+            (0x1000, RefKind::Lea),
+            (0x1007, RefKind::Mov),
+            (0x1020, RefKind::AbsPtr)
+        ]
+    );
+}
+
+#[test]
+fn find_str_refs_32_bit() {
+    // 0x1000: inc eax              ; 40
+    // 0x1001: lea ecx, [0x1040]    ; 8D 0D 40 10 00 00
+    // 0x1007: mov eax, [0x1040]    ; 8B 05 40 10 00 00
+    // 0x1020: dd 0x1040, 0x2000
+    // 0x1040: "Player health"
+    let mut data = vec![0u8; 0x60];
+
+    data[..0xD].copy_from_slice(&[
+        0x40, 0x8D, 0x0D, 0x40, 0x10, 0x00, 0x00, 0x8B, 0x05, 0x40, 0x10, 0x00, 0x00,
+    ]);
+
+    data[0x20..0x24].copy_from_slice(&0x1040_u32.to_le_bytes());
+    data[0x24..0x28].copy_from_slice(&0x2000_u32.to_le_bytes());
+    data[0x40..0x4D].copy_from_slice(b"Player health");
+
+    let result = vcheat::strings::find_str_refs(
+        &data,
+        0x1000,
+        "Player health",
+        vcheat::memory::PointerWidth::Bits32,
+    );
+
+    assert_eq!(result.len(), 1);
+    assert_eq!(
+        result[0]
+            .1
+            .iter()
+            .map(|r| (r.addr, r.kind))
+            .collect::<Vec<(usize, RefKind)>>(),
+        vec![
+            (0x1001, RefKind::Lea),
+            (0x1007, RefKind::Mov),
+            (0x1020, RefKind::AbsPtr)
+        ]
+    );
+
+    // An 8-byte search misses the 4-byte pointer
+    assert!(!vcheat::strings::find_refs(
+        &data,
+        0x1000,
+        0x1040,
+        vcheat::memory::PointerWidth::Bits64
+    )
+    .iter()
+    .any(|r| 0x1020 == r.addr));
+}
@@ -0,0 +1,51 @@
+fn len(code: &[u8]) -> usize {
+    vcheat::x86::decode(code).unwrap().len
+}
+
+#[test]
+fn decode_lengths() {
+    // mov rax, [rip+0x12345678]
+    let insn = vcheat::x86::decode(&[0x48, 0x8B, 0x05, 0x78, 0x56, 0x34, 0x12]).unwrap();
+
+    assert_eq!(insn.len, 7);
+    assert!(insn.rip_relative);
+    assert_eq!(insn.disp, Some(vcheat::x86::Operand { offset: 3, size: 4 }));
+
+    // call rel32
+    let insn = vcheat::x86::decode(&[0xE8, 0x00, 0x00, 0x00, 0x00]).unwrap();
+
+    assert_eq!(insn.rel, Some(vcheat::x86::Operand { offset: 1, size: 4 }));
+
+    // mov rax, imm64
+    let insn = vcheat::x86::decode(&[0x48, 0xB8, 1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+
+    assert_eq!(insn.imm, Some(vcheat::x86::Operand { offset: 2, size: 8 }));
+
+    // mov eax, [moffs64]
+    let insn = vcheat::x86::decode(&[0xA1, 1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+
+    assert!(insn.absolute);
+    assert_eq!(insn.len, 9);
+
+    // mov dword ptr [rsp+0x10], 0x1234 ; sib + disp8 + imm32
+    assert_eq!(len(&[0xC7, 0x44, 0x24, 0x10, 0x34, 0x12, 0x00, 0x00]), 8);
+    // mov word ptr [rax], 0x1234
+    assert_eq!(len(&[0x66, 0xC7, 0x00, 0x34, 0x12]), 5);
+    // test byte ptr [rcx], 1
+    assert_eq!(len(&[0xF6, 0x01, 0x01]), 3);
+    // jne rel32
+    assert_eq!(len(&[0x0F, 0x85, 0x10, 0x00, 0x00, 0x00]), 6);
+    // movzx eax, byte ptr [rcx+rdx*4+0x100]
+    assert_eq!(len(&[0x0F, 0xB6, 0x84, 0x91, 0x00, 0x01, 0x00, 0x00]), 8);
+    // pshufd xmm0, xmm1, 0x1B
+    assert_eq!(len(&[0x66, 0x0F, 0x70, 0xC1, 0x1B]), 5);
+    // vmovups ymm0, [rcx]
+    assert_eq!(len(&[0xC5, 0xFC, 0x10, 0x01]), 4);
+    // vpblendd ymm0, ymm1, ymm2, 0x0F
+    assert_eq!(len(&[0xC4, 0xE3, 0x75, 0x02, 0xC2, 0x0F]), 6);
+    // ret
+    assert_eq!(len(&[0xC3]), 1);
+
+    assert!(vcheat::x86::decode(&[0x48, 0x8B]).is_none());
+    assert!(vcheat::x86::decode(&[0x06]).is_none());
+}